@@ -33,6 +33,24 @@ async fn create_check(size: u64, iters: u64) -> Duration {
     elapsed
 }
 
+async fn create_batch_check(size: u64, iters: u64) -> Duration {
+    let path = Path::new("bench_access_controller_create_batch");
+    let database = Arc::new(Database::with_path(path).await.unwrap());
+    let transaction = database.transaction();
+    let mut journal = transaction.journal();
+    let object_ids = (0..size).collect::<Vec<_>>();
+    let start = Instant::now();
+    for _ in 0..iters {
+        assert!(journal.create_batch(&object_ids, None).await.is_ok());
+    }
+    let elapsed = start.elapsed();
+    drop(journal);
+    drop(transaction);
+    drop(database);
+    assert!(remove_dir_all(path).is_ok());
+    elapsed
+}
+
 fn create(c: &mut Criterion) {
     let size: u64 = 64;
     c.bench_with_input(
@@ -45,5 +63,17 @@ fn create(c: &mut Criterion) {
     );
 }
 
-criterion_group!(access_controller, create);
+fn create_batch(c: &mut Criterion) {
+    let size: u64 = 64;
+    c.bench_with_input(
+        BenchmarkId::new("Journal: create_batch", size),
+        &size,
+        |b, &s| {
+            b.to_async(FuturesExecutor)
+                .iter_custom(|iters| create_batch_check(s, iters));
+        },
+    );
+}
+
+criterion_group!(access_controller, create, create_batch);
 criterion_main!(access_controller);
@@ -2,14 +2,18 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use super::access_controller::ObjectState;
+use super::access_controller::{Access, ObjectState};
+use super::cancellation::CancellationToken;
 use super::snapshot::{JournalSnapshot, TransactionSnapshot};
 use super::task_processor::{Task, TaskProcessor};
 use super::transaction::Anchor as TransactionAnchor;
+use super::transaction::Hooks;
+use super::transaction::Serial as TransactionSerial;
 use super::transaction::ID as TransactionID;
-use super::{Error, PersistenceLayer, Sequencer, Snapshot, Transaction};
+use super::{Error, Hook, PersistenceLayer, Sequencer, Snapshot, Transaction};
 use scc::ebr;
 use scc::hash_map::OccupiedEntry;
+use std::collections::BTreeMap;
 use std::future::Future;
 use std::num::NonZeroU32;
 use std::pin::Pin;
@@ -31,6 +35,19 @@ pub struct Journal<'d, 't, S: Sequencer, P: PersistenceLayer<S>> {
 
     /// [`Anchor`] may outlive the [`Journal`].
     anchor: ebr::Arc<Anchor<S>>,
+
+    /// The number of database objects that the [`Journal`] has newly gained access to.
+    lock_count: usize,
+
+    /// Database objects that the [`Journal`] has newly gained access to grouped by tables.
+    ///
+    /// It is only maintained if a [`LockEscalationPolicy`](super::LockEscalationPolicy) is set,
+    /// and the flag denotes whether access to the database object can be released when the row
+    /// locks are escalated to a table lock.
+    rows: BTreeMap<u64, Vec<(u64, bool)>>,
+
+    /// Hooks to be registered in the [`Transaction`] when the [`Journal`] is submitted.
+    hooks: Hooks<S>,
 }
 
 /// The type of journal identifiers.
@@ -101,6 +118,13 @@ pub(super) struct AwaitResponse<'d> {
 
     /// The placeholder for the result and [`Waker`].
     result_placeholder: Arc<AccessRequestResult>,
+
+    /// The [`CancellationToken`] of the requester.
+    cancellation_token: CancellationToken,
+
+    /// The instant when the request started waiting.
+    #[cfg(feature = "tracing")]
+    wait_started: Instant,
 }
 
 /// [`AwaitEOT`] is returned by an [`Anchor`] for the caller to await the final transaction state
@@ -171,6 +195,31 @@ impl<'d, 't, S: Sequencer, P: PersistenceLayer<S>> Journal<'d, 't, S, P> {
         self.anchor.id()
     }
 
+    /// Returns the number of database objects that the [`Journal`] has newly gained access to.
+    ///
+    /// Database objects that the [`Journal`] already had access to, e.g., through a previously
+    /// submitted [`Journal`] in the same [`Transaction`], are not counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("journal_lock_count")).await.unwrap();
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert!(journal.create(&[1, 2], None).await.is_ok());
+    ///     assert_eq!(journal.lock_count(), 2);
+    /// };
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn lock_count(&self) -> usize {
+        self.lock_count
+    }
+
     /// Submits the [`Journal`] to the [`Transaction`].
     ///
     /// The logical clock of the corresponding [`Transaction`] advances towards the next time
@@ -198,9 +247,64 @@ impl<'d, 't, S: Sequencer, P: PersistenceLayer<S>> Journal<'d, 't, S, P> {
     /// ```
     #[inline]
     #[must_use]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            ret,
+            fields(
+                transaction_id = self.transaction.id(),
+                creation_instant = ?self.anchor.creation_instant,
+            )
+        )
+    )]
     pub fn submit(mut self) -> NonZeroU32 {
+        let submit_instant = self
+            .transaction
+            .submit_journal(&self.anchor, self.log_buffer.take());
         self.transaction
-            .submit_journal(&self.anchor, self.log_buffer.take())
+            .submit_hooks(submit_instant, &mut self.hooks);
+        submit_instant
+    }
+
+    /// Registers a hook that is invoked when the [`Transaction`] is ended.
+    ///
+    /// The hook is registered in the [`Transaction`] when the [`Journal`] is submitted, and it is
+    /// dropped without being invoked if the [`Journal`] is rolled back, e.g., the [`Journal`] is
+    /// dropped without being submitted, or the [`Transaction`] is rolled back to a [`Savepoint`]
+    /// created before the [`Journal`] was submitted. See [`Transaction::register_hook`] for more
+    /// details.
+    ///
+    /// [`Savepoint`]: super::Savepoint
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::{Database, HookEvent};
+    /// use std::path::Path;
+    /// use std::sync::atomic::AtomicBool;
+    /// use std::sync::atomic::Ordering::Relaxed;
+    /// use std::sync::Arc;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("journal_register_hook")).await.unwrap();
+    ///     let mut transaction = database.transaction();
+    ///     let savepoint = transaction.savepoint();
+    ///     let invoked = Arc::new(AtomicBool::new(false));
+    ///     let invoked_clone = invoked.clone();
+    ///     let mut journal = transaction.journal();
+    ///     journal.register_hook(Box::new(move |_| invoked_clone.store(true, Relaxed)));
+    ///     journal.submit();
+    ///
+    ///     assert!(transaction.rollback_to(&savepoint).is_ok());
+    ///     assert!(transaction.commit().await.is_ok());
+    ///     assert!(!invoked.load(Relaxed));
+    /// };
+    /// ```
+    #[inline]
+    pub fn register_hook(&mut self, hook: Hook<S>) {
+        self.hooks.push(0, hook);
     }
 
     /// Captures the current state of the [`Journal`] as a [`Snapshot`].
@@ -253,6 +357,133 @@ impl<'d, 't, S: Sequencer, P: PersistenceLayer<S>> Journal<'d, 't, S, P> {
         Ok(())
     }
 
+    /// Creates database objects with the [`Journal`] without waiting for other transactions.
+    ///
+    /// If any of the database objects could not be created, the database objects created by the
+    /// call are released and handed over to waiting transactions, whereas changes made by the
+    /// [`Journal`] before the call are kept. Only the newly created database objects are written to
+    /// the log.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the database objects could not be created or the log record could
+    /// not be generated. [`Error::Conflict`] is returned immediately if another transaction owns
+    /// any of the database objects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("try_create")).await.unwrap();
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert!(journal.try_create(&[1, 2]).await.is_ok());
+    /// };
+    /// ```
+    #[inline]
+    pub async fn try_create(&mut self, object_ids: &[u64]) -> Result<(), Error> {
+        self.create_batch(object_ids, None)
+            .await
+            .map(|_| ())
+            .map_err(|(error, _)| error)
+    }
+
+    /// Acquires exclusive locks on database objects with the [`Journal`] without waiting for
+    /// other transactions.
+    ///
+    /// If any of the database objects could not be locked, the locks acquired by the call are
+    /// released and handed over to waiting transactions, whereas locks that the [`Journal`]
+    /// already had are kept. Locks are not written to the log since they do not survive a restart
+    /// of the database.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the database objects could not be locked. [`Error::Conflict`] is
+    /// returned immediately if another transaction owns any of the database objects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("try_lock")).await.unwrap();
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert!(journal.try_lock(&[1, 2]).await.is_ok());
+    /// };
+    /// ```
+    #[inline]
+    pub async fn try_lock(&mut self, object_ids: &[u64]) -> Result<(), Error> {
+        self.transaction
+            .database()
+            .access_controller()
+            .acquire_all(Access::Lock, object_ids, self, None)
+            .await
+            .map(|_| ())
+            .map_err(|(_, error)| error)
+    }
+
+    /// Creates a batch of database objects with the [`Journal`].
+    ///
+    /// Returns the number of newly created database objects. If any of the database objects
+    /// could not be created, the database objects created in the batch are released and handed
+    /// over to waiting transactions, whereas changes made by the [`Journal`] before the batch are
+    /// kept. Only the newly created database objects are written to the log.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] and the index of the database object that could not be created. The
+    /// index is equal to the number of database objects in the batch if the log record could not
+    /// be generated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("create_batch")).await.unwrap();
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert_eq!(journal.create_batch(&[1, 2, 3], None).await, Ok(3));
+    /// };
+    /// ```
+    #[inline]
+    pub async fn create_batch(
+        &mut self,
+        object_ids: &[u64],
+        deadline: Option<Instant>,
+    ) -> Result<usize, (Error, usize)> {
+        let access_controller = self.transaction.database().access_controller();
+        let created = access_controller
+            .acquire_all(Access::Create, object_ids, self, deadline)
+            .await
+            .map_err(|(index, error)| (error, index))?;
+        let log_buffer = self.log_buffer.take().map_or_else(Arc::default, |b| b);
+        match self.transaction.database().persistence_layer().create(
+            log_buffer,
+            self.transaction.id(),
+            self.id(),
+            &created,
+        ) {
+            Ok(log_buffer) => {
+                self.log_buffer.replace(log_buffer);
+                Ok(created.len())
+            }
+            Err(error) => {
+                // Release the database objects created in the batch.
+                access_controller.release_all(&created, self).await;
+                Err((error, object_ids.len()))
+            }
+        }
+    }
+
     /// Deletes database objects with the [`Journal`].
     ///
     /// # Errors
@@ -282,11 +513,86 @@ impl<'d, 't, S: Sequencer, P: PersistenceLayer<S>> Journal<'d, 't, S, P> {
         Ok(())
     }
 
+    /// Replaces a database object with a new one with the [`Journal`].
+    ///
+    /// The old database object is deleted and the new database object is created by the same
+    /// [`Journal`], therefore readers see either of them, but never both or none of them. When the
+    /// transaction is committed, the deletion instant of the old database object and the creation
+    /// instant of the new database object become the same; both are reverted if the [`Journal`]
+    /// is rolled back.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the old database object could not be deleted, the new database
+    /// object could not be created, or the log records could not be generated. The deletion of the
+    /// old database object is reverted if an [`Error`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("update")).await.unwrap();
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert!(journal.create(&[1], None).await.is_ok());
+    ///     journal.submit();
+    ///     assert!(transaction.commit().await.is_ok());
+    ///
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert!(journal.update(1, 2, None).await.is_ok());
+    /// };
+    /// ```
+    #[inline]
+    pub async fn update(
+        &mut self,
+        old_object_id: u64,
+        new_object_id: u64,
+        deadline: Option<Instant>,
+    ) -> Result<(), Error> {
+        let access_controller = self.transaction.database().access_controller();
+        let deleted = access_controller
+            .delete(old_object_id, self, deadline)
+            .await?;
+        let mut created = false;
+        let result = match access_controller
+            .create(new_object_id, self, deadline)
+            .await
+        {
+            Ok(result) => {
+                created = result;
+                self.log_update(
+                    deleted.then_some(old_object_id),
+                    created.then_some(new_object_id),
+                )
+            }
+            Err(error) => Err(error),
+        };
+        if result.is_err() {
+            // Revert the deletion and the creation.
+            if created && access_controller.revoke(new_object_id, &self.anchor).await {
+                self.forget_lock();
+            }
+            if deleted && access_controller.revoke(old_object_id, &self.anchor).await {
+                self.forget_lock();
+            }
+        }
+        result
+    }
+
     /// Returns a reference to the [`TaskProcessor`].
     pub(super) fn task_processor(&self) -> &'d TaskProcessor {
         self.transaction.database().task_processor()
     }
 
+    /// Returns the [`CancellationToken`] of the [`Transaction`].
+    pub(super) fn cancellation_token(&self) -> CancellationToken {
+        self.transaction.cancellation_token()
+    }
+
     /// Returns a reference to its [`Anchor`].
     pub(super) fn anchor(&self) -> &ebr::Arc<Anchor<S>> {
         &self.anchor
@@ -301,7 +607,70 @@ impl<'d, 't, S: Sequencer, P: PersistenceLayer<S>> Journal<'d, 't, S, P> {
             transaction,
             log_buffer: None,
             anchor: ebr::Arc::new(Anchor::new(transaction_anchor, transaction.now())),
+            lock_count: 0,
+            rows: BTreeMap::new(),
+            hooks: Hooks::default(),
+        }
+    }
+
+    /// Records that the [`Journal`] has newly gained access to a database object.
+    pub(super) fn record_lock(&mut self) {
+        self.lock_count += 1;
+    }
+
+    /// Records that the [`Journal`] has given up access to a database object.
+    pub(super) fn forget_lock(&mut self) {
+        self.lock_count = self.lock_count.saturating_sub(1);
+    }
+
+    /// Records that the [`Journal`] has newly gained access to a database object in the table.
+    ///
+    /// Returns the number of database objects in the table that the [`Journal`] has gained access
+    /// to.
+    pub(super) fn record_row(&mut self, table: u64, object_id: u64, releasable: bool) -> usize {
+        let rows = self.rows.entry(table).or_default();
+        rows.push((object_id, releasable));
+        rows.len()
+    }
+
+    /// Records that the [`Journal`] has given up access to a database object in the table.
+    pub(super) fn forget_row(&mut self, table: u64, object_id: u64) {
+        if let Some(rows) = self.rows.get_mut(&table) {
+            rows.retain(|(id, _)| *id != object_id);
+        }
+    }
+
+    /// Takes the database objects in the table that the [`Journal`] has gained access to.
+    pub(super) fn take_rows(&mut self, table: u64) -> Vec<(u64, bool)> {
+        self.rows.remove(&table).unwrap_or_default()
+    }
+
+    /// Generates the log records for [`Journal::update`].
+    fn log_update(
+        &mut self,
+        deleted_object_id: Option<u64>,
+        created_object_id: Option<u64>,
+    ) -> Result<(), Error> {
+        let persistence_layer = self.transaction.database().persistence_layer();
+        let mut log_buffer = self.log_buffer.take().map_or_else(Arc::default, |b| b);
+        if let Some(object_id) = deleted_object_id {
+            log_buffer = persistence_layer.delete(
+                log_buffer,
+                self.transaction.id(),
+                self.id(),
+                &[object_id],
+            )?;
         }
+        if let Some(object_id) = created_object_id {
+            log_buffer = persistence_layer.create(
+                log_buffer,
+                self.transaction.id(),
+                self.id(),
+                &[object_id],
+            )?;
+        }
+        self.log_buffer.replace(log_buffer);
+        Ok(())
     }
 
     /// Creates a new [`JournalSnapshot`].
@@ -340,6 +709,11 @@ impl<S: Sequencer> Anchor<S> {
         self.transaction_anchor.as_ptr() as TransactionID
     }
 
+    /// The transaction serial number is returned.
+    pub(super) fn transaction_serial(&self) -> TransactionSerial {
+        self.transaction_anchor.serial()
+    }
+
     /// Gets the end-of-transaction time instant.
     ///
     /// Returns `None` if the transaction is not ended.
@@ -566,6 +940,16 @@ impl AccessRequestResult {
     pub(super) fn lock_sync(&self) -> Option<MutexGuard<ResultWakerPair>> {
         self.result_waker.lock().ok()
     }
+
+    /// Returns `true` if the result has yet to be determined.
+    ///
+    /// It never blocks the thread; the result is regarded as pending if the [`Mutex`] is being
+    /// held by another thread.
+    pub(super) fn is_pending(&self) -> bool {
+        self.result_waker
+            .try_lock()
+            .map_or(true, |result_waker| result_waker.0.is_none())
+    }
 }
 
 impl<'d> AwaitResponse<'d> {
@@ -574,6 +958,7 @@ impl<'d> AwaitResponse<'d> {
         entry: OccupiedEntry<u64, ObjectState<S>>,
         task_processor: &'d TaskProcessor,
         deadline: Instant,
+        cancellation_token: CancellationToken,
         result_placeholder: Arc<AccessRequestResult>,
     ) -> AwaitResponse<'d> {
         let object_id = *entry.key();
@@ -584,8 +969,36 @@ impl<'d> AwaitResponse<'d> {
             task_processor,
             deadline,
             result_placeholder,
+            cancellation_token,
+            #[cfg(feature = "tracing")]
+            wait_started: Instant::now(),
         }
     }
+
+    /// Returns the identifier of the request.
+    fn id(&self) -> usize {
+        Arc::as_ptr(&self.result_placeholder) as usize
+    }
+
+    /// Emits an event describing how the request was resolved.
+    fn trace_response(&self, result: &Result<bool, Error>) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            object_id = self.object_id,
+            wait_duration = ?self.wait_started.elapsed(),
+            result = ?result,
+            "access request resolved after waiting",
+        );
+        #[cfg(not(feature = "tracing"))]
+        let _ = (self, result);
+    }
+}
+
+impl<'d> Drop for AwaitResponse<'d> {
+    #[inline]
+    fn drop(&mut self) {
+        self.cancellation_token.unregister(self.id());
+    }
 }
 
 impl<'d> Future for AwaitResponse<'d> {
@@ -595,13 +1008,21 @@ impl<'d> Future for AwaitResponse<'d> {
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         if let Ok(mut result_waker) = self.result_placeholder.result_waker.try_lock() {
             if let Some(result) = result_waker.0.as_ref() {
+                self.trace_response(result);
                 return Poll::Ready(result.clone());
             }
             if self.deadline < Instant::now() {
                 // The deadline was reached.
                 result_waker.0.replace(Err(Error::Timeout));
+                self.trace_response(&Err(Error::Timeout));
                 return Poll::Ready(Err(Error::Timeout));
             }
+            if self.cancellation_token.register(self.id(), cx.waker()) {
+                // The transaction was cancelled.
+                result_waker.0.replace(Err(Error::Cancelled));
+                self.trace_response(&Err(Error::Cancelled));
+                return Poll::Ready(Err(Error::Cancelled));
+            }
             result_waker.1.replace(cx.waker().clone());
         } else {
             cx.waker().wake_by_ref();
@@ -684,4 +1105,148 @@ mod tests {
         assert_eq!(Some(journal_3.submit()), NonZeroU32::new(4));
         assert!(remove_dir_all(path).await.is_ok());
     }
+
+    #[tokio::test]
+    async fn update() {
+        const DIR: &str = "journal_update_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        let transaction = database.transaction();
+        let mut journal = transaction.journal();
+        assert!(journal.create(&[1], None).await.is_ok());
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+        assert!(transaction.commit().await.is_ok());
+
+        // Both the deletion and creation are reverted if the journal is rolled back.
+        let transaction = database.transaction();
+        let mut journal = transaction.journal();
+        assert!(journal.update(1, 2, None).await.is_ok());
+        drop(journal);
+        let snapshot = transaction.snapshot();
+        assert_eq!(access_controller.read(1, &snapshot, None).await, Ok(true));
+        assert_eq!(access_controller.read(2, &snapshot, None).await, Ok(false));
+        drop(snapshot);
+
+        // The deletion is reverted if the creation fails.
+        let blocker = database.transaction();
+        let mut blocker_journal = blocker.journal();
+        assert!(blocker_journal.create(&[3], None).await.is_ok());
+        let mut journal = transaction.journal();
+        assert_eq!(journal.update(1, 3, None).await, Err(Error::Conflict));
+        assert_eq!(journal.lock_count(), 0);
+        assert_eq!(access_controller.scan().count(), 1);
+        drop(journal);
+        drop(blocker_journal);
+
+        let mut journal = transaction.journal();
+        assert!(journal.update(1, 2, None).await.is_ok());
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+        let old_snapshot = database.snapshot();
+        assert_eq!(
+            access_controller.read(1, &old_snapshot, None).await,
+            Ok(true)
+        );
+        assert_eq!(
+            access_controller.read(2, &old_snapshot, None).await,
+            Ok(false)
+        );
+        assert!(transaction.commit().await.is_ok());
+
+        let snapshot = database.snapshot();
+        assert_eq!(access_controller.read(1, &snapshot, None).await, Ok(false));
+        assert_eq!(access_controller.read(2, &snapshot, None).await, Ok(true));
+        assert_eq!(
+            access_controller.read(1, &old_snapshot, None).await,
+            Ok(true)
+        );
+        assert_eq!(
+            access_controller.read(2, &old_snapshot, None).await,
+            Ok(false)
+        );
+        drop(old_snapshot);
+        drop(snapshot);
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn create_batch() {
+        const DIR: &str = "journal_create_batch_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        let transaction = database.transaction();
+        let mut journal = transaction.journal();
+        assert_eq!(journal.create_batch(&[1, 2, 2], None).await, Ok(2));
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+
+        let other_transaction = database.transaction();
+        let mut other_journal = other_transaction.journal();
+        assert!(other_journal.create(&[3], None).await.is_ok());
+        assert_eq!(
+            other_journal.create_batch(&[4, 5, 1, 6], None).await,
+            Err((Error::Conflict, 2))
+        );
+
+        // The database objects created before the batch are kept.
+        let mut journal = transaction.journal();
+        assert_eq!(
+            journal.create_batch(&[4, 5, 3], None).await,
+            Err((Error::Conflict, 2))
+        );
+        assert_eq!(journal.create_batch(&[4, 5, 6], None).await, Ok(3));
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(2));
+
+        let snapshot = other_journal.snapshot();
+        assert_eq!(access_controller.read(3, &snapshot, None).await, Ok(true));
+        drop(snapshot);
+        assert_eq!(Some(other_journal.submit()), NonZeroU32::new(1));
+        assert!(transaction.commit().await.is_ok());
+        assert!(other_transaction.commit().await.is_ok());
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn try_create_lock() {
+        const DIR: &str = "journal_try_create_lock_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let transaction = database.transaction();
+        let mut journal = transaction.journal();
+        assert!(journal.try_create(&[1]).await.is_ok());
+        assert!(journal.try_lock(&[2]).await.is_ok());
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+
+        let other_transaction = database.transaction();
+        let mut other_journal = other_transaction.journal();
+        assert_eq!(other_journal.try_create(&[1]).await, Err(Error::Conflict));
+        assert_eq!(other_journal.try_lock(&[2]).await, Err(Error::Conflict));
+
+        // The database objects created before the failure are released.
+        assert_eq!(
+            other_journal.try_create(&[5, 1]).await,
+            Err(Error::Conflict)
+        );
+        assert_eq!(other_journal.lock_count(), 0);
+        assert_eq!(database.access_controller().lock_count(), 2);
+
+        // The locks acquired before the failure are released.
+        assert_eq!(other_journal.try_lock(&[4, 2]).await, Err(Error::Conflict));
+        assert_eq!(other_journal.lock_count(), 0);
+        assert_eq!(database.access_controller().lock_count(), 2);
+        assert!(other_journal.try_lock(&[3]).await.is_ok());
+        drop(other_journal);
+
+        // The rolled back journal does not keep any locks.
+        let mut journal = transaction.journal();
+        assert!(journal.try_lock(&[3]).await.is_ok());
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(2));
+        assert!(transaction.commit().await.is_ok());
+
+        let mut other_journal = other_transaction.journal();
+        assert!(other_journal.try_lock(&[2, 3]).await.is_ok());
+        assert_eq!(Some(other_journal.submit()), NonZeroU32::new(1));
+        assert!(other_transaction.commit().await.is_ok());
+        assert!(remove_dir_all(path).await.is_ok());
+    }
 }
@@ -0,0 +1,116 @@
+// SPDX-FileCopyrightText: 2023 Changgyoo Park <wvwwvwwv@me.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::mem::take;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::{Acquire, Release};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::Waker;
+
+/// [`CancellationToken`] interrupts access requests of a [`Transaction`](super::Transaction)
+/// that are waiting for other transactions.
+///
+/// Once [`CancellationToken::cancel`] is called, all the waiting access requests of the
+/// [`Transaction`](super::Transaction) promptly return [`Error::Cancelled`](super::Error), and so
+/// do all the access requests that would have to wait afterwards. Cancellation does not roll back
+/// the [`Transaction`](super::Transaction).
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    /// The state shared among all the clones of the [`CancellationToken`].
+    state: Arc<State>,
+}
+
+/// The state of a [`CancellationToken`].
+#[derive(Debug, Default)]
+struct State {
+    /// `true` if the token was cancelled.
+    cancelled: AtomicBool,
+
+    /// [`Waker`] instances of waiting access requests indexed by their identifiers.
+    wakers: Mutex<HashMap<usize, Waker>>,
+}
+
+impl CancellationToken {
+    /// Cancels all the waiting access requests of the [`Transaction`](super::Transaction).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("cancel")).await.unwrap();
+    ///     let transaction = database.transaction();
+    ///     let token = transaction.cancellation_token();
+    ///     token.cancel();
+    ///     assert!(token.is_cancelled());
+    /// };
+    /// ```
+    #[inline]
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Release);
+        let wakers = take(
+            &mut *self
+                .state
+                .wakers
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        wakers.into_values().for_each(Waker::wake);
+    }
+
+    /// Returns `true` if the [`CancellationToken`] was cancelled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("is_cancelled")).await.unwrap();
+    ///     let transaction = database.transaction();
+    ///     assert!(!transaction.cancellation_token().is_cancelled());
+    /// };
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Acquire)
+    }
+
+    /// Registers the [`Waker`] of a waiting access request.
+    ///
+    /// Returns `true` if the [`CancellationToken`] was cancelled, in which case the [`Waker`] is
+    /// not registered.
+    pub(super) fn register(&self, id: usize, waker: &Waker) -> bool {
+        let mut wakers = self
+            .state
+            .wakers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if self.is_cancelled() {
+            return true;
+        }
+        match wakers.get_mut(&id) {
+            Some(registered) if registered.will_wake(waker) => (),
+            Some(registered) => registered.clone_from(waker),
+            None => {
+                wakers.insert(id, waker.clone());
+            }
+        }
+        false
+    }
+
+    /// Unregisters the [`Waker`] of an access request that is no longer waiting.
+    pub(super) fn unregister(&self, id: usize) {
+        self.state
+            .wakers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&id);
+    }
+}
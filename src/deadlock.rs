@@ -0,0 +1,366 @@
+// SPDX-FileCopyrightText: 2023 Changgyoo Park <wvwwvwwv@me.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use super::journal::AccessRequestResult;
+use super::transaction::Serial as TransactionSerial;
+use super::Error;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// [`WaitForGraph`] records which transactions are waiting for which other transactions.
+///
+/// An edge from a transaction to another transaction is added when the former starts waiting for
+/// a database object owned by the latter, the edge is redirected when ownership of the database
+/// object is transferred to another transaction, and the edge is removed when the wait is over. A
+/// cycle in the graph means that none of the transactions in the cycle is able to make progress.
+///
+/// Transactions are identified by their serial numbers which are never reused, therefore an edge
+/// of a finished transaction cannot be mistaken for an edge of a new transaction.
+#[derive(Debug, Default)]
+pub(super) struct WaitForGraph {
+    /// The edges and waiting requests.
+    state: Mutex<State>,
+}
+
+/// [`WaitEdges`] removes the edges of a waiting request from the [`WaitForGraph`] when dropped.
+#[derive(Debug)]
+pub(super) struct WaitEdges<'g> {
+    /// The [`WaitForGraph`] containing the edges.
+    graph: &'g WaitForGraph,
+
+    /// The result placeholder of the waiting request.
+    ///
+    /// It is kept until the edges are removed so that the address is not reused by another
+    /// request in the meantime.
+    result_placeholder: Arc<AccessRequestResult>,
+}
+
+/// The edges and waiting requests of a [`WaitForGraph`].
+#[derive(Debug, Default)]
+struct State {
+    /// Adjacency lists indexed by transaction serial numbers.
+    ///
+    /// The same edge can be added more than once when multiple journals of a transaction are
+    /// waiting for the same transaction, therefore each edge is reference-counted.
+    adjacency: HashMap<TransactionSerial, HashMap<TransactionSerial, usize>>,
+
+    /// Waiting requests indexed by the address of their result placeholders.
+    waits: HashMap<usize, Wait>,
+}
+
+/// A waiting request.
+#[derive(Debug)]
+struct Wait {
+    /// The waiting transaction.
+    waiter: TransactionSerial,
+
+    /// The transactions being waited for.
+    holders: Vec<TransactionSerial>,
+
+    /// The result placeholder of the request.
+    result_placeholder: Arc<AccessRequestResult>,
+}
+
+impl WaitForGraph {
+    /// Adds edges from the waiter to each of the holders.
+    ///
+    /// The returned [`WaitEdges`] has to be kept until the wait is over; it is dropped when the
+    /// waiter is granted access, reaches its deadline, or stops waiting. If the new edges form a
+    /// cycle, the youngest transaction in the cycle is chosen as the victim since it is likely to
+    /// have done the least amount of work; all the waiting requests of the victim fail with
+    /// [`Error::Deadlock`], and the other transactions in the cycle keep waiting.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Deadlock`] if the waiter is the youngest transaction in a cycle formed by
+    /// the new edges; the new edges are not added to the graph in that case.
+    pub(super) fn wait<I: IntoIterator<Item = TransactionSerial>>(
+        &self,
+        waiter: TransactionSerial,
+        holders: I,
+        result_placeholder: &Arc<AccessRequestResult>,
+    ) -> Result<WaitEdges<'_>, Error> {
+        let key = Self::key(result_placeholder);
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.insert(
+            key,
+            Wait {
+                waiter,
+                holders: Self::normalize(waiter, holders),
+                result_placeholder: result_placeholder.clone(),
+            },
+        );
+        let victims = state.break_cycles(waiter);
+        let is_victim = !state.waits.contains_key(&key);
+        drop(state);
+        for victim in victims.iter().filter(|v| Self::key(v) != key) {
+            Self::abort(victim);
+        }
+        if is_victim {
+            return Err(Error::Deadlock);
+        }
+        Ok(WaitEdges {
+            graph: self,
+            result_placeholder: result_placeholder.clone(),
+        })
+    }
+
+    /// Redirects the edges of a waiting request to the new holders.
+    ///
+    /// It is invoked when ownership of the database object is transferred while the request is
+    /// still waiting. If the redirected edges form a cycle, all the waiting requests of the
+    /// youngest transaction in the cycle fail with [`Error::Deadlock`].
+    pub(super) fn redirect<I: IntoIterator<Item = TransactionSerial>>(
+        &self,
+        result_placeholder: &Arc<AccessRequestResult>,
+        holders: I,
+    ) {
+        let key = Self::key(result_placeholder);
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(wait) = state.waits.get(&key) else {
+            // The request is not waiting.
+            return;
+        };
+        let waiter = wait.waiter;
+        let holders = Self::normalize(waiter, holders);
+        if wait.holders == holders {
+            return;
+        }
+        let Some(wait) = state.remove(key) else {
+            return;
+        };
+        state.insert(
+            key,
+            Wait {
+                waiter,
+                holders,
+                result_placeholder: wait.result_placeholder,
+            },
+        );
+        let victims = state.break_cycles(waiter);
+        drop(state);
+        for victim in &victims {
+            Self::abort(victim);
+        }
+    }
+
+    /// Returns the key of a waiting request.
+    fn key(result_placeholder: &Arc<AccessRequestResult>) -> usize {
+        Arc::as_ptr(result_placeholder) as usize
+    }
+
+    /// Returns the sorted and deduplicated holders excluding the waiter.
+    fn normalize<I: IntoIterator<Item = TransactionSerial>>(
+        waiter: TransactionSerial,
+        holders: I,
+    ) -> Vec<TransactionSerial> {
+        let mut holders: Vec<TransactionSerial> =
+            holders.into_iter().filter(|h| *h != waiter).collect();
+        holders.sort_unstable();
+        holders.dedup();
+        holders
+    }
+
+    /// Makes the waiting request fail with [`Error::Deadlock`].
+    fn abort(result_placeholder: &AccessRequestResult) {
+        if let Some(mut result_waker) = result_placeholder.lock_sync() {
+            if result_waker.0.is_none() {
+                result_waker.0.replace(Err(Error::Deadlock));
+            }
+            if let Some(waker) = result_waker.1.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl State {
+    /// Inserts a waiting request and its edges.
+    fn insert(&mut self, key: usize, wait: Wait) {
+        if !wait.holders.is_empty() {
+            let edges = self.adjacency.entry(wait.waiter).or_default();
+            for holder in &wait.holders {
+                *edges.entry(*holder).or_default() += 1;
+            }
+        }
+        self.waits.insert(key, wait);
+    }
+
+    /// Removes a waiting request and its edges.
+    fn remove(&mut self, key: usize) -> Option<Wait> {
+        let wait = self.waits.remove(&key)?;
+        if let Some(edges) = self.adjacency.get_mut(&wait.waiter) {
+            for holder in &wait.holders {
+                if let Some(count) = edges.get_mut(holder) {
+                    *count -= 1;
+                    if *count == 0 {
+                        edges.remove(holder);
+                    }
+                }
+            }
+            if edges.is_empty() {
+                self.adjacency.remove(&wait.waiter);
+            }
+        }
+        Some(wait)
+    }
+
+    /// Breaks all the cycles that pass through the specified transaction.
+    ///
+    /// The youngest transaction in each cycle is chosen as the victim, and all the waiting requests
+    /// of the victim are removed. Returns the result placeholders of the removed requests.
+    fn break_cycles(&mut self, serial: TransactionSerial) -> Vec<Arc<AccessRequestResult>> {
+        let mut victims = Vec::new();
+        while let Some(cycle) = self.find_cycle(serial) {
+            let Some(victim) = cycle.into_iter().max() else {
+                break;
+            };
+            let keys: Vec<usize> = self
+                .waits
+                .iter()
+                .filter_map(|(key, wait)| (wait.waiter == victim).then_some(*key))
+                .collect();
+            for key in keys {
+                if let Some(wait) = self.remove(key) {
+                    victims.push(wait.result_placeholder);
+                }
+            }
+        }
+        victims
+    }
+
+    /// Returns the transactions in a cycle that passes through the specified transaction.
+    fn find_cycle(&self, serial: TransactionSerial) -> Option<Vec<TransactionSerial>> {
+        let mut predecessors = HashMap::new();
+        let mut stack = vec![serial];
+        while let Some(current) = stack.pop() {
+            let Some(edges) = self.adjacency.get(&current) else {
+                continue;
+            };
+            for next in edges.keys() {
+                if *next == serial {
+                    let mut cycle = vec![current];
+                    let mut member = current;
+                    while let Some(predecessor) = predecessors.get(&member) {
+                        member = *predecessor;
+                        cycle.push(member);
+                    }
+                    return Some(cycle);
+                } else if !predecessors.contains_key(next) {
+                    predecessors.insert(*next, current);
+                    stack.push(*next);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<'g> Drop for WaitEdges<'g> {
+    #[inline]
+    fn drop(&mut self) {
+        let key = WaitForGraph::key(&self.result_placeholder);
+        self.graph
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_empty(graph: &WaitForGraph) -> bool {
+        let state = graph.state.lock().unwrap();
+        state.adjacency.is_empty() && state.waits.is_empty()
+    }
+
+    #[test]
+    fn wait_for_graph() {
+        let graph = WaitForGraph::default();
+        let requests: Vec<Arc<AccessRequestResult>> = (0..5).map(|_| Arc::default()).collect();
+        let edges_1_2 = graph.wait(1, [2], &requests[0]).unwrap();
+        let edges_2_3 = graph.wait(2, [3, 2], &requests[1]).unwrap();
+        assert_eq!(
+            graph.wait(3, [1], &requests[2]).err(),
+            Some(Error::Deadlock)
+        );
+        drop(edges_1_2);
+        let edges_3_1 = graph.wait(3, [1], &requests[3]).unwrap();
+
+        // The youngest transaction in the cycle is chosen as the victim.
+        let edges_1_4_2 = graph.wait(1, [4, 2], &requests[4]).unwrap();
+        assert_eq!(
+            requests[3].lock_sync().unwrap().0.clone(),
+            Some(Err(Error::Deadlock))
+        );
+        assert!(requests[4].is_pending());
+        drop(edges_1_4_2);
+        drop(edges_2_3);
+        drop(edges_3_1);
+        assert!(is_empty(&graph));
+    }
+
+    #[test]
+    fn wait_for_graph_ring() {
+        let graph = WaitForGraph::default();
+        let num_transactions = 16;
+        let requests: Vec<Arc<AccessRequestResult>> =
+            (0..=num_transactions).map(|_| Arc::default()).collect();
+        let mut wait_edges: Vec<WaitEdges> = (0..num_transactions - 1)
+            .map(|i| graph.wait(i, [i + 1], &requests[i as usize]).unwrap())
+            .collect();
+        assert_eq!(
+            graph
+                .wait(
+                    num_transactions - 1,
+                    [0],
+                    &requests[num_transactions as usize - 1]
+                )
+                .err(),
+            Some(Error::Deadlock)
+        );
+
+        // Once any transaction stops waiting, the ring is broken.
+        wait_edges.remove(8);
+        wait_edges.push(
+            graph
+                .wait(
+                    num_transactions - 1,
+                    [0],
+                    &requests[num_transactions as usize],
+                )
+                .unwrap(),
+        );
+        wait_edges.clear();
+        assert!(is_empty(&graph));
+    }
+
+    #[test]
+    fn wait_for_graph_redirect() {
+        let graph = WaitForGraph::default();
+        let requests: Vec<Arc<AccessRequestResult>> = (0..3).map(|_| Arc::default()).collect();
+        let edges_1_2 = graph.wait(1, [2], &requests[0]).unwrap();
+        let edges_3_4 = graph.wait(3, [4], &requests[1]).unwrap();
+
+        // Ownership is transferred from 4 to 5 which is not waiting.
+        graph.redirect(&requests[1], [5]);
+        assert!(requests[1].is_pending());
+
+        // Ownership is transferred from 5 to 1 which is indirectly waiting for 3.
+        let edges_2_3 = graph.wait(2, [3], &requests[2]).unwrap();
+        graph.redirect(&requests[1], [1]);
+        assert_eq!(
+            requests[1].lock_sync().unwrap().0.clone(),
+            Some(Err(Error::Deadlock))
+        );
+
+        drop(edges_1_2);
+        drop(edges_2_3);
+        drop(edges_3_4);
+        assert!(is_empty(&graph));
+    }
+}
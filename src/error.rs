@@ -2,18 +2,129 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
 /// [`Error`] defines all the error codes used in the lock table implementation.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Error {
+    /// The operation was cancelled through a [`CancellationToken`](super::CancellationToken).
+    Cancelled,
+
     /// The operation conflicts with others.
+    ///
+    /// It is returned without waiting if access to a database object is requested without a
+    /// deadline while another transaction owns the database object, regardless of whether shared
+    /// or exclusive access was requested.
     Conflict,
 
     /// The operation causes a deadlock.
+    ///
+    /// If transactions are waiting for each other in a cycle, the youngest transaction in the cycle
+    /// fails with this error, and the other transactions keep waiting.
     Deadlock,
 
+    /// The [`Journal`](super::Journal) has reached the maximum number of locks.
+    LockLimitExceeded,
+
     /// Memory allocation failed.
     OutOfMemory,
 
+    /// The transaction cannot be serialized with concurrent transactions.
+    SerializationFailure,
+
+    /// The snapshot is older than the garbage collection horizon.
+    SnapshotTooOld,
+
     /// The operation was timed out.
     Timeout,
+
+    /// The operation was attempted in an unexpected state.
+    ///
+    /// It optionally carries the underlying error, e.g., an I/O error that the persistence layer
+    /// encountered, which is returned by [`source`](std::error::Error::source).
+    UnexpectedState(Option<Cause>),
+
+    /// A wrong parameter was supplied.
+    WrongParameter,
+}
+
+/// [`Cause`] is the underlying error of an [`Error`].
+///
+/// Two [`Cause`] instances are equal only if they share the same underlying error.
+#[derive(Clone, Debug)]
+pub struct Cause(Arc<dyn std::error::Error + Send + Sync>);
+
+impl Cause {
+    /// Creates a new [`Cause`] from an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::{Cause, Error};
+    /// use std::error::Error as _;
+    /// use std::io;
+    ///
+    /// let cause = io::Error::other("disk full");
+    /// let error = Error::UnexpectedState(Some(Cause::new(cause)));
+    /// assert_eq!(error.source().unwrap().to_string(), "disk full");
+    /// ```
+    #[inline]
+    pub fn new<E: std::error::Error + Send + Sync + 'static>(error: E) -> Cause {
+        Cause(Arc::new(error))
+    }
+}
+
+impl fmt::Display for Cause {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Eq for Cause {}
+
+impl PartialEq for Cause {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Error::Cancelled => "operation cancelled",
+            Error::Conflict => "conflict with a concurrent transaction",
+            Error::Deadlock => "deadlock detected",
+            Error::LockLimitExceeded => "too many locks acquired by a single journal",
+            Error::OutOfMemory => "memory allocation failed",
+            Error::SerializationFailure => "serialization failure",
+            Error::SnapshotTooOld => "snapshot older than the garbage collection horizon",
+            Error::Timeout => "operation timed out",
+            Error::UnexpectedState(None) => "unexpected state",
+            Error::UnexpectedState(Some(cause)) => return write!(f, "unexpected state: {cause}"),
+            Error::WrongParameter => "wrong parameter",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::UnexpectedState(Some(cause)) => Some(cause.0.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    #[inline]
+    fn from(error: io::Error) -> Self {
+        Error::UnexpectedState(Some(Cause::new(error)))
+    }
 }
@@ -13,16 +13,29 @@
 //! Transactional Lock Table
 
 mod lock_table;
-pub use lock_table::AccessController;
+pub use lock_table::{AccessController, LockEscalationPolicy, LockInfo, LockMode};
+
+mod deadlock;
+
+mod metrics;
+
+mod range_lock;
+
+mod statistics;
+#[cfg(feature = "statistics")]
+pub use statistics::{Statistics, WAIT_TIME_BUCKETS};
 
 mod error;
-pub use error::Error;
+pub use error::{Cause, Error};
 
 mod accessor;
 pub use accessor::Journal;
 
+mod cancellation;
+pub use cancellation::CancellationToken;
+
 mod transaction;
-pub use transaction::{Committable, Transaction};
+pub use transaction::{Committable, Hook, HookEvent, Savepoint, Transaction};
 
 pub mod utils;
 
@@ -2,18 +2,27 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+use super::deadlock::WaitForGraph;
 use super::journal::AccessRequestResult;
 use super::journal::Anchor as JournalAnchor;
 use super::journal::{AwaitResponse, Relationship};
+use super::metrics;
+use super::range_lock::RangeLockTable;
+use super::statistics::Collector;
+#[cfg(feature = "statistics")]
+use super::statistics::Statistics;
 use super::{Error, Journal, PersistenceLayer, Sequencer, Snapshot};
 use scc::hash_map::Entry as MapEntry;
 use scc::{ebr, HashMap};
 use std::cmp;
 use std::collections::{BTreeSet, VecDeque};
 use std::mem::take;
-use std::ops::{Deref, DerefMut};
-use std::sync::Arc;
-use std::time::Instant;
+use std::num::NonZeroUsize;
+use std::ops::{Deref, DerefMut, Range};
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::{Duration, Instant};
 
 /// [`AccessController`] grants or rejects access to a database object identified as a [`usize`]
 /// value.
@@ -90,6 +99,67 @@ use std::time::Instant;
 #[derive(Debug, Default)]
 pub struct AccessController<S: Sequencer> {
     table: HashMap<u64, ObjectState<S>>,
+    wait_for_graph: WaitForGraph,
+    gc_horizon: RwLock<S::Instant>,
+    range_locks: RangeLockTable<S>,
+    max_locks_per_journal: AtomicUsize,
+    lock_count: AtomicUsize,
+    lock_escalation_policy: RwLock<Option<LockEscalationPolicy>>,
+    statistics: Collector,
+}
+
+/// Lock modes that can be passed to [`AccessController::lock_for`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LockMode {
+    /// A shared lock acquired via [`AccessController::share`].
+    Shared,
+
+    /// An exclusive lock acquired via [`AccessController::lock`].
+    Exclusive,
+}
+
+/// Types of access that [`AccessController::acquire_all`] gains.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum Access {
+    /// Creates database objects via [`AccessController::create`].
+    Create,
+
+    /// Locks database objects via [`AccessController::lock`].
+    Lock,
+}
+
+/// Lock escalation policies that can be passed to
+/// [`AccessController::set_lock_escalation_policy`].
+///
+/// A table is identified by the bits of database object identifiers above the lowest `row_bits`
+/// bits, e.g., database objects `0x1_0000` and `0x1_ffff` belong to the same table if `row_bits`
+/// is `16`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LockEscalationPolicy {
+    /// The number of database objects in a table that a [`Journal`] can newly gain access to
+    /// before the row locks are escalated to a table lock.
+    pub threshold: usize,
+
+    /// The number of lower bits of database object identifiers that identify rows in a table.
+    pub row_bits: u32,
+}
+
+/// [`LockInfo`] describes a database object owned by active transactions.
+///
+/// [`AccessController::scan`] returns a [`LockInfo`] for each database object owned by active
+/// transactions.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LockInfo {
+    /// The identifier of the database object.
+    object_id: u64,
+
+    /// The lock mode.
+    ///
+    /// A database object being created or deleted is exclusively locked.
+    mode: LockMode,
+
+    /// The identifiers of the transactions owning the database object.
+    owners: Vec<u64>,
 }
 
 /// An owner of a database object.
@@ -224,6 +294,8 @@ impl<S: Sequencer> AccessController<S> {
     /// An [`Error`] is returned if the specified deadline was reached or memory allocation failed
     /// when pushing a [`Waker`](std::task::Waker) into the owner
     /// [`Transaction`](super::Transaction) if the transaction is being committed.
+    /// [`Error::SnapshotTooOld`] is returned if the [`Snapshot`] predates the garbage collection
+    /// horizon.
     ///
     /// # Examples
     ///
@@ -251,6 +323,10 @@ impl<S: Sequencer> AccessController<S> {
         snapshot: &Snapshot<'_, '_, '_, S>,
         deadline: Option<Instant>,
     ) -> Result<bool, Error> {
+        if *snapshot < self.gc_horizon() {
+            // Database object versions that the snapshot may need to read could have been pruned.
+            return Err(Error::SnapshotTooOld);
+        }
         loop {
             let await_eot = match self
                 .table
@@ -325,6 +401,279 @@ impl<S: Sequencer> AccessController<S> {
         Ok(true)
     }
 
+    /// Returns the garbage collection horizon.
+    ///
+    /// Database object versions older than the garbage collection horizon may have been pruned,
+    /// and [`Snapshot`] instances predating it cannot be used to read database objects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("gc_horizon")).await.unwrap();
+    ///     let access_controller = database.access_controller();
+    ///     assert_eq!(access_controller.gc_horizon(), Default::default());
+    /// };
+    /// ```
+    #[inline]
+    pub fn gc_horizon(&self) -> S::Instant {
+        self.gc_horizon
+            .read()
+            .map_or_else(|e| *e.into_inner(), |h| *h)
+    }
+
+    /// Advances the garbage collection horizon to the specified instant.
+    ///
+    /// The garbage collector must advance the horizon before pruning database object versions
+    /// older than the instant. The background garbage collector of the [`Database`] advances the
+    /// horizon to the oldest live [`Snapshot`] on its own, therefore it only needs to be invoked
+    /// when database object versions are pruned outside of it. The garbage collection horizon
+    /// never moves backwards, and the updated horizon is returned.
+    ///
+    /// [`Database`]: super::Database
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("advance_gc_horizon")).await.unwrap();
+    ///     let access_controller = database.access_controller();
+    ///     let transaction = database.transaction();
+    ///     let commit_instant = transaction.commit().await.unwrap();
+    ///     assert_eq!(access_controller.advance_gc_horizon(commit_instant), commit_instant);
+    /// };
+    /// ```
+    #[inline]
+    pub fn advance_gc_horizon(&self, instant: S::Instant) -> S::Instant {
+        let mut gc_horizon = self
+            .gc_horizon
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        if *gc_horizon < instant {
+            *gc_horizon = instant;
+        }
+        *gc_horizon
+    }
+
+    /// Returns the maximum number of database objects that a single [`Journal`] can newly gain
+    /// access to.
+    ///
+    /// `None` is returned if there is no limit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("max_locks_per_journal")).await.unwrap();
+    ///     let access_controller = database.access_controller();
+    ///     assert!(access_controller.max_locks_per_journal().is_none());
+    /// };
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn max_locks_per_journal(&self) -> Option<NonZeroUsize> {
+        NonZeroUsize::new(self.max_locks_per_journal.load(Relaxed))
+    }
+
+    /// Sets the maximum number of database objects that a single [`Journal`] can newly gain
+    /// access to.
+    ///
+    /// Once a [`Journal`] has reached the limit, [`AccessController::create`],
+    /// [`AccessController::share`], [`AccessController::lock`], and [`AccessController::delete`]
+    /// fail with [`Error::LockLimitExceeded`], and the caller is expected to submit the
+    /// [`Journal`] and continue with a new one. `None` removes the limit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::{Database, Error};
+    /// use std::num::NonZeroUsize;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("set_max_locks")).await.unwrap();
+    ///     let access_controller = database.access_controller();
+    ///     access_controller.set_max_locks_per_journal(NonZeroUsize::new(1));
+    ///
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert!(access_controller.lock(1, &mut journal, None).await.is_ok());
+    ///     assert_eq!(
+    ///         access_controller.lock(2, &mut journal, None).await,
+    ///         Err(Error::LockLimitExceeded)
+    ///     );
+    /// };
+    /// ```
+    #[inline]
+    pub fn set_max_locks_per_journal(&self, limit: Option<NonZeroUsize>) {
+        self.max_locks_per_journal
+            .store(limit.map_or(0, NonZeroUsize::get), Relaxed);
+    }
+
+    /// Returns the lock escalation policy.
+    ///
+    /// `None` is returned if row locks are never escalated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("lock_escalation_policy")).await.unwrap();
+    ///     let access_controller = database.access_controller();
+    ///     assert!(access_controller.lock_escalation_policy().is_none());
+    /// };
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn lock_escalation_policy(&self) -> Option<LockEscalationPolicy> {
+        *self
+            .lock_escalation_policy
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Sets the lock escalation policy.
+    ///
+    /// Once a [`Journal`] has newly gained access to `threshold` database objects in a table via
+    /// [`AccessController::create`] or [`AccessController::lock`], it acquires an exclusive table
+    /// lock that prevents other transactions from gaining access to database objects in the table,
+    /// and then releases its own locks on the database objects in the table; access to the
+    /// database objects that the [`Journal`] created is retained. [`Error::LockLimitExceeded`] is
+    /// returned if another transaction has locked the table or gained access to a database object
+    /// in the table, and other transactions fail to gain access to database objects in a locked table
+    /// with [`Error::Conflict`]. The table lock is released when the transaction is ended or the
+    /// [`Journal`] is rolled back. `None` disables lock escalation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::{Database, Error, LockEscalationPolicy};
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("set_lock_escalation")).await.unwrap();
+    ///     let access_controller = database.access_controller();
+    ///     access_controller.set_lock_escalation_policy(Some(LockEscalationPolicy {
+    ///         threshold: 2,
+    ///         row_bits: 16,
+    ///     }));
+    ///
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert!(access_controller.lock(0x1_0001, &mut journal, None).await.is_ok());
+    ///     assert!(access_controller.lock(0x1_0002, &mut journal, None).await.is_ok());
+    ///     assert_eq!(journal.lock_count(), 1);
+    ///
+    ///     let other_transaction = database.transaction();
+    ///     let mut other_journal = other_transaction.journal();
+    ///     assert_eq!(
+    ///         access_controller.lock(0x1_0003, &mut other_journal, None).await,
+    ///         Err(Error::Conflict)
+    ///     );
+    /// };
+    /// ```
+    #[inline]
+    pub fn set_lock_escalation_policy(&self, lock_escalation_policy: Option<LockEscalationPolicy>) {
+        *self
+            .lock_escalation_policy
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = lock_escalation_policy;
+    }
+
+    /// Returns a snapshot of the lock acquisition statistics.
+    ///
+    /// It is only available if the `statistics` feature is enabled; the counters are maintained
+    /// with relaxed atomic operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("statistics")).await.unwrap();
+    ///     let access_controller = database.access_controller();
+    ///     assert_eq!(access_controller.statistics().waits(), 0);
+    ///
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert!(access_controller.create(1, &mut journal, None).await.is_ok());
+    ///     assert_eq!(access_controller.statistics().acquisitions(), 1);
+    /// };
+    /// ```
+    #[cfg(feature = "statistics")]
+    #[inline]
+    #[must_use]
+    pub fn statistics(&self) -> Statistics {
+        self.statistics.snapshot()
+    }
+
+    /// Returns at most `n` most frequently contended database objects.
+    ///
+    /// A database object is contended when a transaction has to wait for it, or when gaining
+    /// access to it fails because of a conflict or a deadlock. The counts are estimated by a
+    /// count-min sketch, and therefore the returned counts may be larger than the actual counts.
+    /// The returned pairs of a database object identifier and its estimated count are sorted in
+    /// descending order of the count. It is only available if the `statistics` feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("top_contended")).await.unwrap();
+    ///     let access_controller = database.access_controller();
+    ///     assert!(access_controller.top_contended(4).is_empty());
+    /// };
+    /// ```
+    #[cfg(feature = "statistics")]
+    #[inline]
+    #[must_use]
+    pub fn top_contended(&self, n: usize) -> Vec<(u64, u32)> {
+        self.statistics.top_contended(n)
+    }
+
+    /// Resets the lock acquisition statistics.
+    ///
+    /// It is only available if the `statistics` feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("reset_statistics")).await.unwrap();
+    ///     let access_controller = database.access_controller();
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert!(access_controller.create(1, &mut journal, None).await.is_ok());
+    ///     access_controller.reset_statistics();
+    ///     assert_eq!(access_controller.statistics().acquisitions(), 0);
+    /// };
+    /// ```
+    #[cfg(feature = "statistics")]
+    #[inline]
+    pub fn reset_statistics(&self) {
+        self.statistics.reset();
+    }
+
     /// Creates access control data for a newly created database object.
     ///
     /// The access control data is atomically converted into a time point data when the transaction
@@ -350,7 +699,10 @@ impl<S: Sequencer> AccessController<S> {
     ///
     /// An [`Error`] is returned if memory allocation failed, the database object was already
     /// created, or another transaction could not complete creating the database object until the
-    /// deadline was reached.
+    /// deadline was reached. [`Error::Conflict`] is returned without waiting if no deadline is
+    /// specified and another transaction is creating the database object, or if another active
+    /// transaction holds a range lock covering the database object. [`Error::LockLimitExceeded`]
+    /// is returned if the row locks of the journal could not be escalated to a table lock.
     ///
     /// # Examples
     ///
@@ -373,10 +725,36 @@ impl<S: Sequencer> AccessController<S> {
         journal: &mut Journal<'_, '_, S, P>,
         deadline: Option<Instant>,
     ) -> Result<bool, Error> {
+        self.check_lock_limit(journal)?;
+        let result = self.create_inner(object_id, journal, deadline).await;
+        let result = self.check_table_lock(object_id, journal, result).await;
+        metrics::record_access(&self.statistics, object_id, &result);
+        if let Ok(true) = result {
+            journal.record_lock();
+            self.escalate(object_id, journal, false).await?;
+        }
+        result
+    }
+
+    /// Creates access control data for the database object without checking the lock limit.
+    async fn create_inner<P: PersistenceLayer<S>>(
+        &self,
+        object_id: u64,
+        journal: &mut Journal<'_, '_, S, P>,
+        deadline: Option<Instant>,
+    ) -> Result<bool, Error> {
+        if self
+            .range_locks
+            .is_locked_by_others(object_id, journal.anchor())
+        {
+            // Another transaction prevents phantom database objects from being created.
+            return Err(Error::Conflict);
+        }
         let mut entry = match self.table.entry_async(object_id).await {
             MapEntry::Occupied(entry) => entry,
             MapEntry::Vacant(entry) => {
                 entry.insert_entry(ObjectState::Owned(Ownership::Created(Owner::from(journal))));
+                self.record_entry_inserted();
                 return Ok(true);
             }
         };
@@ -389,30 +767,98 @@ impl<S: Sequencer> AccessController<S> {
             ObjectState::Owned(Ownership::CreatedAwaitable(exclusive_awaitable)),
         ) = (deadline, entry.get_mut())
         {
-            let task_processor = journal.task_processor();
             let result_placeholder = Arc::new(AccessRequestResult::default());
+            let _wait_edges = self.wait_for_graph.wait(
+                journal.anchor().transaction_serial(),
+                [exclusive_awaitable.owner.transaction_serial()],
+                &result_placeholder,
+            )?;
+            let task_processor = journal.task_processor();
+            let cancellation_token = journal.cancellation_token();
             let request = Request::Create(
                 Instant::now(),
                 Owner::from(journal),
                 result_placeholder.clone(),
             );
             exclusive_awaitable.push_request(request);
-            return AwaitResponse::new(entry, task_processor, deadline, result_placeholder).await;
+            return metrics::wait(
+                &self.statistics,
+                object_id,
+                AwaitResponse::new(
+                    entry,
+                    task_processor,
+                    deadline,
+                    cancellation_token,
+                    result_placeholder,
+                ),
+            )
+            .await;
         }
 
         // The database object has been created, deleted, or invisible.
         Err(Error::SerializationFailure)
     }
 
+    /// Creates access control data for a set of newly created database objects.
+    ///
+    /// The database objects are created in ascending order of their identifiers regardless of the
+    /// order in the slice, so that transactions creating overlapping sets of database objects
+    /// cannot deadlock each other. If any of the database objects could not be created, the
+    /// database objects created by this method are released and handed over to waiting
+    /// transactions before the error is returned.
+    ///
+    /// Returns the number of database objects for which new access control data was created.
+    ///
+    /// # Errors
+    ///
+    /// The same [`Error`] as [`AccessController::create`] is returned if one of the database
+    /// objects could not be created.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("bulk_create")).await.unwrap();
+    ///     let access_controller = database.access_controller();
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert_eq!(access_controller.bulk_create(&[3, 1, 2], &mut journal, None).await, Ok(3));
+    /// };
+    /// ```
+    #[inline]
+    pub async fn bulk_create<P: PersistenceLayer<S>>(
+        &self,
+        object_ids: &[u64],
+        journal: &mut Journal<'_, '_, S, P>,
+        deadline: Option<Instant>,
+    ) -> Result<usize, Error> {
+        let mut sorted_object_ids = object_ids.to_vec();
+        sorted_object_ids.sort_unstable();
+        sorted_object_ids.dedup();
+        self.acquire_all(Access::Create, &sorted_object_ids, journal, deadline)
+            .await
+            .map(|created| created.len())
+            .map_err(|(_, error)| error)
+    }
+
     /// Acquires a shared lock on the database object to protect it against modification attempts.
     ///
+    /// Any number of transactions can share the same database object at the same time, whereas a
+    /// shared lock conflicts with exclusive ownership of the database object.
+    ///
     /// Returns `true` if the journal successfully acquired a new shared lock. `false` is returned
     /// if the transaction already has ownership of the database object.
     ///
     /// # Errors
     ///
     /// An [`Error`] is returned if the transaction failed to protect the database object, memory
-    /// allocation failed, or the specified deadline was reached.
+    /// allocation failed, or the specified deadline was reached. [`Error::Conflict`] is returned
+    /// if the database object was deleted by a committed transaction, if no deadline is specified
+    /// and another transaction owns the database object, or if another active transaction holds a
+    /// table lock covering the database object.
     ///
     /// # Examples
     ///
@@ -440,6 +886,30 @@ impl<S: Sequencer> AccessController<S> {
         object_id: u64,
         journal: &mut Journal<'_, '_, S, P>,
         deadline: Option<Instant>,
+    ) -> Result<bool, Error> {
+        if self
+            .range_locks
+            .is_exclusively_locked_by(object_id, journal.anchor())
+        {
+            // The transaction holds the table lock.
+            return Ok(false);
+        }
+        self.check_lock_limit(journal)?;
+        let result = self.share_inner(object_id, journal, deadline).await;
+        let result = self.check_table_lock(object_id, journal, result).await;
+        metrics::record_access(&self.statistics, object_id, &result);
+        if let Ok(true) = result {
+            journal.record_lock();
+        }
+        result
+    }
+
+    /// Acquires a shared lock on the database object without checking the lock limit.
+    async fn share_inner<P: PersistenceLayer<S>>(
+        &self,
+        object_id: u64,
+        journal: &mut Journal<'_, '_, S, P>,
+        deadline: Option<Instant>,
     ) -> Result<bool, Error> {
         let mut entry = match self.table.entry_async(object_id).await {
             MapEntry::Occupied(entry) => entry,
@@ -447,6 +917,7 @@ impl<S: Sequencer> AccessController<S> {
                 entry.insert_entry(ObjectState::Owned(Ownership::Protected(Owner::from(
                     journal,
                 ))));
+                self.record_entry_inserted();
                 return Ok(true);
             }
         };
@@ -460,38 +931,63 @@ impl<S: Sequencer> AccessController<S> {
                 | Ownership::LockedAwaitable(exclusive_awaitable)
                 | Ownership::DeletedAwaitable(exclusive_awaitable) => {
                     if let Some(deadline) = deadline {
-                        let task_processor = journal.task_processor();
                         let result_placeholder = Arc::new(AccessRequestResult::default());
+                        let _wait_edges = self.wait_for_graph.wait(
+                            journal.anchor().transaction_serial(),
+                            [exclusive_awaitable.owner.transaction_serial()],
+                            &result_placeholder,
+                        )?;
+                        let task_processor = journal.task_processor();
+                        let cancellation_token = journal.cancellation_token();
                         let request = Request::Protect(
                             Instant::now(),
                             Owner::from(journal),
                             result_placeholder.clone(),
                         );
                         exclusive_awaitable.push_request(request);
-                        return AwaitResponse::new(
-                            entry,
-                            task_processor,
-                            deadline,
-                            result_placeholder,
+                        return metrics::wait(
+                            &self.statistics,
+                            object_id,
+                            AwaitResponse::new(
+                                entry,
+                                task_processor,
+                                deadline,
+                                cancellation_token,
+                                result_placeholder,
+                            ),
                         )
                         .await;
                     }
                 }
                 Ownership::ProtectedAwaitable(shared_awaitable) => {
                     if let Some(deadline) = deadline {
-                        let task_processor = journal.task_processor();
                         let result_placeholder = Arc::new(AccessRequestResult::default());
+                        let _wait_edges = self.wait_for_graph.wait(
+                            journal.anchor().transaction_serial(),
+                            shared_awaitable
+                                .owner_set
+                                .iter()
+                                .map(|o| o.transaction_serial()),
+                            &result_placeholder,
+                        )?;
+                        let task_processor = journal.task_processor();
+                        let cancellation_token = journal.cancellation_token();
                         let request = Request::Protect(
                             Instant::now(),
                             Owner::from(journal),
                             result_placeholder.clone(),
                         );
                         shared_awaitable.push_request(request);
-                        return AwaitResponse::new(
-                            entry,
-                            task_processor,
-                            deadline,
-                            result_placeholder,
+                        return metrics::wait(
+                            &self.statistics,
+                            object_id,
+                            AwaitResponse::new(
+                                entry,
+                                task_processor,
+                                deadline,
+                                cancellation_token,
+                                result_placeholder,
+                            ),
                         )
                         .await;
                     }
@@ -501,18 +997,29 @@ impl<S: Sequencer> AccessController<S> {
         }
 
         // The database object has been deleted, or invisible.
-        Err(Error::SerializationFailure)
+        Err(Error::Conflict)
     }
 
     /// Acquires the exclusive lock on the database object.
     ///
+    /// A shared lock held by the transaction is promoted to the exclusive lock if no other
+    /// transactions share the database object. If other transactions share the database object,
+    /// the journal waits for them to release their shared locks until the deadline is reached.
+    ///
     /// Returns `true` if the journal successfully acquired the exclusive lock. `false` is returned
     /// if the transaction already has exclusive ownership of the database object.
     ///
     /// # Errors
     ///
     /// An [`Error`] is returned if the exclusive access request was denied, memory allocation
-    /// failed, or the specified deadline was reached.
+    /// failed, or the specified deadline was reached. [`Error::Conflict`] is returned if the
+    /// database object was deleted by a committed transaction, or if no deadline is specified and
+    /// another transaction owns the database object. [`Error::Deadlock`] is returned if the
+    /// transaction shares the database object, and another transaction sharing it has already been
+    /// waiting for exclusive access. [`Error::Conflict`] is also returned if another active
+    /// transaction holds a table lock covering the database object, and
+    /// [`Error::LockLimitExceeded`] if the row locks of the journal could not be escalated to a
+    /// table lock.
     ///
     /// # Examples
     ///
@@ -540,11 +1047,37 @@ impl<S: Sequencer> AccessController<S> {
         object_id: u64,
         journal: &mut Journal<'_, '_, S, P>,
         deadline: Option<Instant>,
+    ) -> Result<bool, Error> {
+        if self
+            .range_locks
+            .is_exclusively_locked_by(object_id, journal.anchor())
+        {
+            // The transaction holds the table lock.
+            return Ok(false);
+        }
+        self.check_lock_limit(journal)?;
+        let result = self.lock_inner(object_id, journal, deadline).await;
+        let result = self.check_table_lock(object_id, journal, result).await;
+        metrics::record_access(&self.statistics, object_id, &result);
+        if let Ok(true) = result {
+            journal.record_lock();
+            self.escalate(object_id, journal, true).await?;
+        }
+        result
+    }
+
+    /// Acquires an exclusive lock on the database object without checking the lock limit.
+    async fn lock_inner<P: PersistenceLayer<S>>(
+        &self,
+        object_id: u64,
+        journal: &mut Journal<'_, '_, S, P>,
+        deadline: Option<Instant>,
     ) -> Result<bool, Error> {
         let mut entry = match self.table.entry_async(object_id).await {
             MapEntry::Occupied(entry) => entry,
             MapEntry::Vacant(entry) => {
                 entry.insert_entry(ObjectState::Owned(Ownership::Locked(Owner::from(journal))));
+                self.record_entry_inserted();
                 return Ok(true);
             }
         };
@@ -558,38 +1091,63 @@ impl<S: Sequencer> AccessController<S> {
                 | Ownership::LockedAwaitable(exclusive_awaitable)
                 | Ownership::DeletedAwaitable(exclusive_awaitable) => {
                     if let Some(deadline) = deadline {
-                        let task_processor = journal.task_processor();
                         let result_placeholder = Arc::new(AccessRequestResult::default());
+                        let _wait_edges = self.wait_for_graph.wait(
+                            journal.anchor().transaction_serial(),
+                            [exclusive_awaitable.owner.transaction_serial()],
+                            &result_placeholder,
+                        )?;
+                        let task_processor = journal.task_processor();
+                        let cancellation_token = journal.cancellation_token();
                         let request = Request::Lock(
                             Instant::now(),
                             Owner::from(journal),
                             result_placeholder.clone(),
                         );
                         exclusive_awaitable.push_request(request);
-                        return AwaitResponse::new(
-                            entry,
-                            task_processor,
-                            deadline,
-                            result_placeholder,
+                        return metrics::wait(
+                            &self.statistics,
+                            object_id,
+                            AwaitResponse::new(
+                                entry,
+                                task_processor,
+                                deadline,
+                                cancellation_token,
+                                result_placeholder,
+                            ),
                         )
                         .await;
                     }
                 }
                 Ownership::ProtectedAwaitable(shared_awaitable) => {
                     if let Some(deadline) = deadline {
-                        let task_processor = journal.task_processor();
                         let result_placeholder = Arc::new(AccessRequestResult::default());
+                        let _wait_edges = self.wait_for_graph.wait(
+                            journal.anchor().transaction_serial(),
+                            shared_awaitable
+                                .owner_set
+                                .iter()
+                                .map(|o| o.transaction_serial()),
+                            &result_placeholder,
+                        )?;
+                        let task_processor = journal.task_processor();
+                        let cancellation_token = journal.cancellation_token();
                         let request = Request::Lock(
                             Instant::now(),
                             Owner::from(journal),
                             result_placeholder.clone(),
                         );
                         shared_awaitable.push_request(request);
-                        return AwaitResponse::new(
-                            entry,
-                            task_processor,
-                            deadline,
-                            result_placeholder,
+                        return metrics::wait(
+                            &self.statistics,
+                            object_id,
+                            AwaitResponse::new(
+                                entry,
+                                task_processor,
+                                deadline,
+                                cancellation_token,
+                                result_placeholder,
+                            ),
                         )
                         .await;
                     }
@@ -599,55 +1157,336 @@ impl<S: Sequencer> AccessController<S> {
         }
 
         // The database object has been deleted, or invisible.
-        Err(Error::SerializationFailure)
+        Err(Error::Conflict)
     }
 
-    /// Takes ownership of the database object for deletion.
+    /// Acquires a lock on the database object in the specified mode, waiting at most for the
+    /// specified duration.
     ///
-    /// The access control data is atomically converted into a time point data when the transaction
-    /// is committed, so that readers can check if they have the read access right to the data. No
-    /// other transactions are allowed to modify the access control data after it was deleted.
+    /// [`Duration::ZERO`] means that the journal never waits for the lock; [`Error::Conflict`] is
+    /// immediately returned if the lock cannot be granted at once.
     ///
-    /// Returns `true` if the database object was newly deleted in the transaction.
+    /// Returns `true` if the journal successfully acquired a new lock.
     ///
     /// # Errors
     ///
-    /// An [`Error`] is returned if the database object was deleted, memory allocation failed, or
-    /// the specified deadline was reached.
+    /// An [`Error`] is returned if the lock could not be acquired. [`Error::Timeout`] is returned
+    /// if the lock was not granted within the specified duration.
     ///
     /// # Examples
     ///
     /// ```
-    /// use sap_tsf::Database;
+    /// use sap_tsf::{Database, LockMode};
     /// use std::path::Path;
+    /// use std::time::Duration;
     ///
     /// async {
-    ///     let database = Database::with_path(Path::new("delete")).await.unwrap();
+    ///     let database = Database::with_path(Path::new("lock_for")).await.unwrap();
     ///     let access_controller = database.access_controller();
     ///     let transaction = database.transaction();
     ///     let mut journal = transaction.journal();
-    ///     assert!(access_controller.create(1, &mut journal, None).await.is_ok());
-    ///     journal.submit();
-    ///     assert!(transaction.commit().await.is_ok());
-    ///
-    ///     let transaction = database.transaction();
-    ///     let mut journal = transaction.journal();
-    ///     assert_eq!(access_controller.delete(1, &mut journal, None).await, Ok(true));
+    ///     assert_eq!(
+    ///         access_controller
+    ///             .lock_for(1, &mut journal, LockMode::Exclusive, Duration::from_millis(50))
+    ///             .await,
+    ///         Ok(true)
+    ///     );
     /// };
     /// ```
     #[inline]
-    pub async fn delete<P: PersistenceLayer<S>>(
+    pub async fn lock_for<P: PersistenceLayer<S>>(
         &self,
         object_id: u64,
         journal: &mut Journal<'_, '_, S, P>,
-        deadline: Option<Instant>,
+        mode: LockMode,
+        timeout: Duration,
     ) -> Result<bool, Error> {
-        let mut entry = match self.table.entry_async(object_id).await {
-            MapEntry::Occupied(entry) => entry,
-            MapEntry::Vacant(entry) => {
-                entry.insert_entry(ObjectState::Owned(Ownership::Deleted(Owner::from(journal))));
-                return Ok(true);
-            }
+        let deadline = if timeout.is_zero() {
+            None
+        } else {
+            Some(Instant::now() + timeout)
+        };
+        match mode {
+            LockMode::Shared => self.share(object_id, journal, deadline).await,
+            LockMode::Exclusive => self.lock(object_id, journal, deadline).await,
+        }
+    }
+
+    /// Acquires exclusive locks on a set of database objects, all or nothing.
+    ///
+    /// The database objects are locked in ascending order of their identifiers regardless of the
+    /// order in the slice; as long as every transaction locking more than one database object at
+    /// a time does so through [`AccessController::lock_all`] or
+    /// [`AccessController::lock_all_with_retry`], transactions locking overlapping sets of
+    /// database objects cannot deadlock each other. No lock is waited for, and if any of the
+    /// database objects could not be locked, exactly the locks acquired by this method are
+    /// released and handed over to waiting transactions before the error is returned; locks that
+    /// the journal or the transaction already had are kept.
+    ///
+    /// Returns the number of database objects that were newly locked.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::Conflict`] is returned if any of the database objects could not be locked for
+    /// whatever reason.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("lock_all")).await.unwrap();
+    ///     let access_controller = database.access_controller();
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert_eq!(access_controller.lock_all(&[3, 1, 2], &mut journal).await, Ok(3));
+    /// };
+    /// ```
+    #[inline]
+    pub async fn lock_all<P: PersistenceLayer<S>>(
+        &self,
+        object_ids: &[u64],
+        journal: &mut Journal<'_, '_, S, P>,
+    ) -> Result<usize, Error> {
+        let mut sorted_object_ids = object_ids.to_vec();
+        sorted_object_ids.sort_unstable();
+        sorted_object_ids.dedup();
+        self.acquire_all(Access::Lock, &sorted_object_ids, journal, None)
+            .await
+            .map(|locked| locked.len())
+            .map_err(|_| Error::Conflict)
+    }
+
+    /// Acquires exclusive locks on a set of database objects, all or nothing, waiting for
+    /// conflicting transactions.
+    ///
+    /// It works in the same way as [`AccessController::lock_all`] except that, if one of the
+    /// database objects is owned by another transaction, it waits for the database object to be
+    /// released while keeping the locks acquired so far, and restarts locking the rest of the
+    /// database objects. The method gives up after restarting `max_retries` times if
+    /// `max_retries` is specified, and the locks acquired by this method are released and handed
+    /// over to waiting transactions before the error is returned.
+    ///
+    /// Returns the number of database objects that were newly locked.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::Conflict`] is returned if the maximum number of retries was reached, or one of the
+    /// database objects could not be locked for reasons other than waiting. An [`Error`] that
+    /// occurred while waiting for a conflicting transaction, e.g., [`Error::Timeout`] or
+    /// [`Error::Deadlock`], is returned as it is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("lock_all_with_retry")).await.unwrap();
+    ///     let access_controller = database.access_controller();
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     let deadline = Instant::now() + Duration::from_secs(1);
+    ///     assert_eq!(
+    ///         access_controller
+    ///             .lock_all_with_retry(&[3, 1, 2], &mut journal, deadline, Some(4))
+    ///             .await,
+    ///         Ok(3)
+    ///     );
+    /// };
+    /// ```
+    #[inline]
+    pub async fn lock_all_with_retry<P: PersistenceLayer<S>>(
+        &self,
+        object_ids: &[u64],
+        journal: &mut Journal<'_, '_, S, P>,
+        deadline: Instant,
+        max_retries: Option<usize>,
+    ) -> Result<usize, Error> {
+        let mut sorted_object_ids = object_ids.to_vec();
+        sorted_object_ids.sort_unstable();
+        sorted_object_ids.dedup();
+
+        let mut locked = Vec::with_capacity(sorted_object_ids.len());
+        let mut start = 0;
+        let mut retries = 0;
+        loop {
+            let error = match self
+                .acquire_each(
+                    Access::Lock,
+                    &sorted_object_ids[start..],
+                    journal,
+                    None,
+                    &mut locked,
+                )
+                .await
+            {
+                Ok(()) => return Ok(locked.len()),
+                Err((index, Error::Conflict)) if max_retries.map_or(true, |m| retries < m) => {
+                    // Wait for the conflicting transaction, and restart from the database object.
+                    retries += 1;
+                    start += index;
+                    match self
+                        .acquire_each(
+                            Access::Lock,
+                            &sorted_object_ids[start..=start],
+                            journal,
+                            Some(deadline),
+                            &mut locked,
+                        )
+                        .await
+                    {
+                        Ok(()) => {
+                            start += 1;
+                            continue;
+                        }
+                        Err((_, error)) => error,
+                    }
+                }
+                Err(_) => Error::Conflict,
+            };
+            self.release_all(&locked, journal).await;
+            return Err(error);
+        }
+    }
+
+    /// Gains access to all the database objects in the supplied order, or none of them.
+    ///
+    /// Returns the identifiers of the database objects that the journal newly gained access to.
+    /// If access to one of the database objects could not be gained, access gained by this method
+    /// is released and handed over to waiting transactions, and the index of the database object
+    /// is returned along with the [`Error`]; access that the journal or the transaction already
+    /// had is kept.
+    pub(super) async fn acquire_all<P: PersistenceLayer<S>>(
+        &self,
+        access: Access,
+        object_ids: &[u64],
+        journal: &mut Journal<'_, '_, S, P>,
+        deadline: Option<Instant>,
+    ) -> Result<Vec<u64>, (usize, Error)> {
+        let mut acquired = Vec::with_capacity(object_ids.len());
+        if let Err(failure) = self
+            .acquire_each(access, object_ids, journal, deadline, &mut acquired)
+            .await
+        {
+            self.release_all(&acquired, journal).await;
+            return Err(failure);
+        }
+        Ok(acquired)
+    }
+
+    /// Releases access to the database objects that the journal acquired, and hands it over to
+    /// waiting transactions.
+    pub(super) async fn release_all<P: PersistenceLayer<S>>(
+        &self,
+        object_ids: &[u64],
+        journal: &mut Journal<'_, '_, S, P>,
+    ) {
+        for object_id in object_ids {
+            if self.revoke(*object_id, journal.anchor()).await {
+                journal.forget_lock();
+            }
+        }
+    }
+
+    /// Gains access to the database objects in the supplied order.
+    ///
+    /// The identifiers of the database objects that the journal newly gained access to are pushed
+    /// into `acquired`, and the index of the database object that could not be accessed is
+    /// returned along with the [`Error`] without releasing anything.
+    async fn acquire_each<P: PersistenceLayer<S>>(
+        &self,
+        access: Access,
+        object_ids: &[u64],
+        journal: &mut Journal<'_, '_, S, P>,
+        deadline: Option<Instant>,
+        acquired: &mut Vec<u64>,
+    ) -> Result<(), (usize, Error)> {
+        for (index, object_id) in object_ids.iter().enumerate() {
+            let result = match access {
+                Access::Create => self.create(*object_id, journal, deadline).await,
+                Access::Lock => self.lock(*object_id, journal, deadline).await,
+            };
+            match result {
+                Ok(true) => acquired.push(*object_id),
+                Ok(false) => (),
+                Err(error) => return Err((index, error)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Takes ownership of the database object for deletion.
+    ///
+    /// The access control data is atomically converted into a time point data when the transaction
+    /// is committed, so that readers can check if they have the read access right to the data. No
+    /// other transactions are allowed to modify the access control data after it was deleted.
+    ///
+    /// Returns `true` if the database object was newly deleted in the transaction.
+    ///
+    /// # Errors
+    ///
+    /// An [`Error`] is returned if memory allocation failed, or the specified deadline was reached.
+    /// [`Error::Conflict`] is returned if the database object was deleted by a committed
+    /// transaction, or if no deadline is specified and another transaction owns the database
+    /// object. [`Error::Deadlock`] is returned if the transaction shares the database object, and
+    /// another transaction sharing it has already been waiting for exclusive access.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("delete")).await.unwrap();
+    ///     let access_controller = database.access_controller();
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert!(access_controller.create(1, &mut journal, None).await.is_ok());
+    ///     journal.submit();
+    ///     assert!(transaction.commit().await.is_ok());
+    ///
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert_eq!(access_controller.delete(1, &mut journal, None).await, Ok(true));
+    /// };
+    /// ```
+    #[inline]
+    pub async fn delete<P: PersistenceLayer<S>>(
+        &self,
+        object_id: u64,
+        journal: &mut Journal<'_, '_, S, P>,
+        deadline: Option<Instant>,
+    ) -> Result<bool, Error> {
+        self.check_lock_limit(journal)?;
+        let result = self.delete_inner(object_id, journal, deadline).await;
+        let result = self.check_table_lock(object_id, journal, result).await;
+        metrics::record_access(&self.statistics, object_id, &result);
+        if let Ok(true) = result {
+            journal.record_lock();
+        }
+        result
+    }
+
+    /// Takes ownership of the database object for deletion without checking the lock limit.
+    async fn delete_inner<P: PersistenceLayer<S>>(
+        &self,
+        object_id: u64,
+        journal: &mut Journal<'_, '_, S, P>,
+        deadline: Option<Instant>,
+    ) -> Result<bool, Error> {
+        let mut entry = match self.table.entry_async(object_id).await {
+            MapEntry::Occupied(entry) => entry,
+            MapEntry::Vacant(entry) => {
+                entry.insert_entry(ObjectState::Owned(Ownership::Deleted(Owner::from(journal))));
+                self.record_entry_inserted();
+                return Ok(true);
+            }
         };
 
         let result = Self::try_delete(entry.get_mut(), journal.anchor(), deadline)?;
@@ -659,19 +1498,30 @@ impl<S: Sequencer> AccessController<S> {
                 | Ownership::LockedAwaitable(exclusive_awaitable)
                 | Ownership::DeletedAwaitable(exclusive_awaitable) => {
                     if let Some(deadline) = deadline {
-                        let task_processor = journal.task_processor();
                         let result_placeholder = Arc::new(AccessRequestResult::default());
+                        let _wait_edges = self.wait_for_graph.wait(
+                            journal.anchor().transaction_serial(),
+                            [exclusive_awaitable.owner.transaction_serial()],
+                            &result_placeholder,
+                        )?;
+                        let task_processor = journal.task_processor();
+                        let cancellation_token = journal.cancellation_token();
                         let request = Request::Delete(
                             Instant::now(),
                             Owner::from(journal),
                             result_placeholder.clone(),
                         );
                         exclusive_awaitable.push_request(request);
-                        return AwaitResponse::new(
-                            entry,
-                            task_processor,
-                            deadline,
-                            result_placeholder,
+                        return metrics::wait(
+                            &self.statistics,
+                            object_id,
+                            AwaitResponse::new(
+                                entry,
+                                task_processor,
+                                deadline,
+                                cancellation_token,
+                                result_placeholder,
+                            ),
                         )
                         .await;
                     }
@@ -679,19 +1529,33 @@ impl<S: Sequencer> AccessController<S> {
                 Ownership::ProtectedAwaitable(shared_awaitable) => {
                     if let Some(deadline) = deadline {
                         // Wait for the database resource to be available to the transaction.
-                        let task_processor = journal.task_processor();
                         let result_placeholder = Arc::new(AccessRequestResult::default());
+                        let _wait_edges = self.wait_for_graph.wait(
+                            journal.anchor().transaction_serial(),
+                            shared_awaitable
+                                .owner_set
+                                .iter()
+                                .map(|o| o.transaction_serial()),
+                            &result_placeholder,
+                        )?;
+                        let task_processor = journal.task_processor();
+                        let cancellation_token = journal.cancellation_token();
                         let request = Request::Delete(
                             Instant::now(),
                             Owner::from(journal),
                             result_placeholder.clone(),
                         );
                         shared_awaitable.push_request(request);
-                        return AwaitResponse::new(
-                            entry,
-                            task_processor,
-                            deadline,
-                            result_placeholder,
+                        return metrics::wait(
+                            &self.statistics,
+                            object_id,
+                            AwaitResponse::new(
+                                entry,
+                                task_processor,
+                                deadline,
+                                cancellation_token,
+                                result_placeholder,
+                            ),
                         )
                         .await;
                     }
@@ -701,96 +1565,321 @@ impl<S: Sequencer> AccessController<S> {
         }
 
         // The database object has been deleted, or invisible.
-        Err(Error::SerializationFailure)
+        Err(Error::Conflict)
     }
 
-    /// Creates a new database object during database recovery.
+    /// Downgrades the exclusive lock on the database object to a shared lock.
     ///
-    /// It is an infallible method.
-    pub(crate) fn playback_create_sync(
+    /// The exclusive lock must have been acquired by the transaction, and the journal has to be
+    /// able to see the exclusive lock, e.g., the exclusive lock was acquired by the journal itself
+    /// or a journal submitted earlier. The exclusive lock owner keeps the database object as a
+    /// shared owner, and transactions waiting for a shared lock on the database object are
+    /// immediately granted the shared lock.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::Conflict`] is returned if the transaction does not exclusively lock the database
+    /// object, or the exclusive lock is not visible to the journal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("downgrade")).await.unwrap();
+    ///     let access_controller = database.access_controller();
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert_eq!(access_controller.lock(1, &mut journal, None).await, Ok(true));
+    ///     assert!(access_controller.downgrade(1, &mut journal).await.is_ok());
+    ///     journal.submit();
+    ///
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert_eq!(access_controller.share(1, &mut journal, None).await, Ok(true));
+    /// };
+    /// ```
+    #[inline]
+    pub async fn downgrade<P: PersistenceLayer<S>>(
         &self,
         object_id: u64,
-        journal_anchor: &ebr::Arc<JournalAnchor<S>>,
-    ) {
-        match self.table.entry(object_id) {
-            MapEntry::Occupied(mut o) => {
-                *o.get_mut() = ObjectState::Owned(Ownership::Created(Owner {
-                    anchor: journal_anchor.clone(),
-                }));
-            }
-            MapEntry::Vacant(v) => {
-                v.insert_entry(ObjectState::Owned(Ownership::Created(Owner {
-                    anchor: journal_anchor.clone(),
-                })));
-            }
+        journal: &mut Journal<'_, '_, S, P>,
+    ) -> Result<(), Error> {
+        let MapEntry::Occupied(mut entry) = self.table.entry_async(object_id).await else {
+            // The database object is not owned by any transaction.
+            return Err(Error::Conflict);
         };
+        Self::try_downgrade(entry.get_mut(), journal.anchor())?;
+
+        // Shared lock requests in the wait queue can be processed immediately.
+        Self::transfer_ownership(entry.get_mut());
+        self.refresh_wait_edges(entry.get());
+        Ok(())
     }
 
-    /// Deletes a database object during database recovery.
+    /// Acquires a shared lock on a range of database object identifiers to prevent other
+    /// transactions from creating database objects in the range.
     ///
-    /// It is an infallible method.
-    pub(crate) fn playback_delete_sync(
+    /// Shared range locks are compatible with each other, therefore any number of transactions can
+    /// lock overlapping ranges at the same time, and [`AccessController::create`] fails with
+    /// [`Error::Conflict`] if the database object is in a range locked by another transaction. The
+    /// range lock is released when the transaction is ended or the journal is rolled back.
+    ///
+    /// Database objects that other transactions have created before the range was locked are not
+    /// affected by the range lock.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Conflict`] if another transaction holds a table lock overlapping the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::{Database, Error};
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("lock_range")).await.unwrap();
+    ///     let access_controller = database.access_controller();
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert!(access_controller.lock_range(10..20, &mut journal).is_ok());
+    ///     journal.submit();
+    ///
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert_eq!(access_controller.create(15, &mut journal, None).await, Err(Error::Conflict));
+    /// };
+    /// ```
+    #[inline]
+    pub fn lock_range<P: PersistenceLayer<S>>(
         &self,
-        object_id: u64,
-        journal_anchor: &ebr::Arc<JournalAnchor<S>>,
-    ) {
-        match self.table.entry(object_id) {
-            MapEntry::Occupied(mut o) => {
-                *o.get_mut() = ObjectState::Owned(Ownership::Deleted(Owner {
-                    anchor: journal_anchor.clone(),
-                }));
-            }
-            MapEntry::Vacant(v) => {
-                v.insert_entry(ObjectState::Owned(Ownership::Deleted(Owner {
-                    anchor: journal_anchor.clone(),
-                })));
-            }
-        };
+        range: Range<u64>,
+        journal: &mut Journal<'_, '_, S, P>,
+    ) -> Result<(), Error> {
+        if self.range_locks.lock_shared(range, journal.anchor()) {
+            Ok(())
+        } else {
+            Err(Error::Conflict)
+        }
     }
 
-    /// Transfers ownership to all the eligible waiting transactions.
+    /// Returns an iterator over all the database objects owned by active transactions.
     ///
-    /// If the database object still need to be monitored, it returns `true`. It is a blocking and
-    /// synchronous method, therefore this must be invoked in the background.
-    pub(super) fn transfer_ownership_sync(&self, object_id: u64) -> bool {
-        self.table
-            .update(&object_id, |_, object_state| {
-                object_state.prepare_ownership_transfer();
-                let wait_queue = if let ObjectState::Owned(ownership) = object_state {
-                    match ownership {
-                        Ownership::Created(_)
-                        | Ownership::Protected(_)
-                        | Ownership::Locked(_)
-                        | Ownership::Deleted(_) => None,
-                        Ownership::CreatedAwaitable(exclusive_awaitable)
-                        | Ownership::LockedAwaitable(exclusive_awaitable)
-                        | Ownership::DeletedAwaitable(exclusive_awaitable) => {
-                            let wait_queue = take(&mut exclusive_awaitable.wait_queue);
-                            Self::process_wait_queue(object_state, wait_queue)
-                        }
-                        Ownership::ProtectedAwaitable(shared_awaitable) => {
-                            let wait_queue = take(&mut shared_awaitable.wait_queue);
-                            Self::process_wait_queue(object_state, wait_queue)
-                        }
-                    }
-                } else {
-                    None
-                };
-                Self::post_process_object_state(object_state, wait_queue)
-            })
-            .map_or(false, |r| r)
-    }
-
-    /// Tries to remove the access control data corresponding to the database object.
+    /// The access control data is copied before the iterator is returned, therefore the iterator
+    /// does not prevent transactions from gaining access to database objects, and changes made
+    /// afterwards are not reflected in the iterator.
     ///
-    /// Returns `true` if no longer access control data exists for the database object. It is a
-    /// blocking and synchronous method, therefore this must be invoked in the background.
-    pub(super) fn try_remove_access_data_sync<C: Fn(&S::Instant) -> bool, D: FnMut(&S::Instant)>(
-        &self,
-        object_id: u64,
-        condition: &C,
-        deletion_notifier: &mut D,
-    ) -> bool {
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::{Database, LockMode};
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("scan")).await.unwrap();
+    ///     let access_controller = database.access_controller();
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert!(access_controller.share(1, &mut journal, None).await.is_ok());
+    ///
+    ///     let lock_info = access_controller.scan().next().unwrap();
+    ///     assert_eq!(lock_info.object_id(), 1);
+    ///     assert_eq!(lock_info.mode(), LockMode::Shared);
+    ///     assert_eq!(lock_info.owners(), &[transaction.id()]);
+    /// };
+    /// ```
+    #[inline]
+    pub fn scan(&self) -> impl Iterator<Item = LockInfo> {
+        let mut lock_info_list = Vec::new();
+        self.table.scan(|object_id, object_state| {
+            if let Some(lock_info) = Self::lock_info(*object_id, object_state) {
+                lock_info_list.push(lock_info);
+            }
+        });
+        lock_info_list.into_iter()
+    }
+
+    /// Returns the number of database objects owned by transactions.
+    ///
+    /// The number is maintained whenever access control data is inserted or removed, therefore
+    /// it is returned without scanning the [`AccessController`]. Ownership of ended transactions
+    /// is released lazily, and database objects are counted until their access control data is
+    /// cleaned up, whereas [`AccessController::scan`] only returns database objects owned by active
+    /// transactions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("lock_count")).await.unwrap();
+    ///     let access_controller = database.access_controller();
+    ///     let transaction = database.transaction();
+    ///     let mut journal = transaction.journal();
+    ///     assert!(access_controller.lock(1, &mut journal, None).await.is_ok());
+    ///     assert_eq!(access_controller.lock_count(), 1);
+    /// };
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn lock_count(&self) -> usize {
+        self.lock_count.load(Relaxed)
+    }
+
+    /// Revokes ownership of the database object acquired by the journal.
+    ///
+    /// The database object is restored to the state before the journal took ownership of it, and
+    /// ownership is transferred to waiting transactions in the same way as when the journal is
+    /// rolled back. Returns `false` if the journal does not own the database object.
+    pub(super) async fn revoke(
+        &self,
+        object_id: u64,
+        journal_anchor: &ebr::Arc<JournalAnchor<S>>,
+    ) -> bool {
+        let MapEntry::Occupied(mut entry) = self.table.entry_async(object_id).await else {
+            return false;
+        };
+        let (prior_state, mut wait_queue) = match entry.get_mut() {
+            ObjectState::Owned(
+                Ownership::Created(owner)
+                | Ownership::Protected(owner)
+                | Ownership::Locked(owner)
+                | Ownership::Deleted(owner),
+            ) if owner.anchor.as_ptr() == journal_anchor.as_ptr() => (None, WaitQueue::default()),
+            ObjectState::Owned(Ownership::ProtectedAwaitable(shared_awaitable))
+                if shared_awaitable
+                    .owner_set
+                    .iter()
+                    .any(|o| o.anchor.as_ptr() == journal_anchor.as_ptr()) =>
+            {
+                shared_awaitable
+                    .owner_set
+                    .retain(|o| o.anchor.as_ptr() != journal_anchor.as_ptr());
+                if !shared_awaitable.owner_set.is_empty() {
+                    // Other transactions still share the database object.
+                    Self::transfer_ownership(entry.get_mut());
+                    self.refresh_wait_edges(entry.get());
+                    return true;
+                }
+                let prior_state = if shared_awaitable.creation_instant == S::Instant::default() {
+                    None
+                } else {
+                    Some(ObjectState::Created(shared_awaitable.creation_instant))
+                };
+                (prior_state, take(&mut shared_awaitable.wait_queue))
+            }
+            ObjectState::Owned(
+                Ownership::CreatedAwaitable(exclusive_awaitable)
+                | Ownership::LockedAwaitable(exclusive_awaitable)
+                | Ownership::DeletedAwaitable(exclusive_awaitable),
+            ) if exclusive_awaitable.owner.anchor.as_ptr() == journal_anchor.as_ptr() => {
+                let prior_state =
+                    if let Some(prior_ownership) = exclusive_awaitable.prior_ownership.take() {
+                        // Ownership was promoted in the same transaction.
+                        Some(ObjectState::Owned(*prior_ownership))
+                    } else if exclusive_awaitable.creation_instant == S::Instant::default() {
+                        None
+                    } else {
+                        Some(ObjectState::Created(exclusive_awaitable.creation_instant))
+                    };
+                (prior_state, take(&mut exclusive_awaitable.wait_queue))
+            }
+            _ => return false,
+        };
+        if let Some(mut object_state) =
+            prior_state.or_else(|| Self::grant_oldest_request(&mut wait_queue))
+        {
+            let wait_queue = Self::process_wait_queue(&mut object_state, wait_queue);
+            Self::post_process_object_state(&mut object_state, wait_queue);
+            *entry.get_mut() = object_state;
+            self.refresh_wait_edges(entry.get());
+        } else {
+            entry.remove();
+            self.record_entry_removed();
+        }
+        true
+    }
+
+    /// Creates a new database object during database recovery.
+    ///
+    /// It is an infallible method.
+    pub(crate) fn playback_create_sync(
+        &self,
+        object_id: u64,
+        journal_anchor: &ebr::Arc<JournalAnchor<S>>,
+    ) {
+        match self.table.entry(object_id) {
+            MapEntry::Occupied(mut o) => {
+                *o.get_mut() = ObjectState::Owned(Ownership::Created(Owner {
+                    anchor: journal_anchor.clone(),
+                }));
+            }
+            MapEntry::Vacant(v) => {
+                v.insert_entry(ObjectState::Owned(Ownership::Created(Owner {
+                    anchor: journal_anchor.clone(),
+                })));
+                self.record_entry_inserted();
+            }
+        };
+    }
+
+    /// Deletes a database object during database recovery.
+    ///
+    /// It is an infallible method.
+    pub(crate) fn playback_delete_sync(
+        &self,
+        object_id: u64,
+        journal_anchor: &ebr::Arc<JournalAnchor<S>>,
+    ) {
+        match self.table.entry(object_id) {
+            MapEntry::Occupied(mut o) => {
+                *o.get_mut() = ObjectState::Owned(Ownership::Deleted(Owner {
+                    anchor: journal_anchor.clone(),
+                }));
+            }
+            MapEntry::Vacant(v) => {
+                v.insert_entry(ObjectState::Owned(Ownership::Deleted(Owner {
+                    anchor: journal_anchor.clone(),
+                })));
+                self.record_entry_inserted();
+            }
+        };
+    }
+
+    /// Removes range locks held by ended transactions or rolled back journals.
+    pub(super) fn prune_range_locks(&self) {
+        self.range_locks.prune();
+    }
+
+    /// Transfers ownership to all the eligible waiting transactions.
+    ///
+    /// If the database object still need to be monitored, it returns `true`. It is a blocking and
+    /// synchronous method, therefore this must be invoked in the background.
+    pub(super) fn transfer_ownership_sync(&self, object_id: u64) -> bool {
+        self.table
+            .update(&object_id, |_, object_state| {
+                let result = Self::transfer_ownership(object_state);
+                self.refresh_wait_edges(object_state);
+                result
+            })
+            .map_or(false, |r| r)
+    }
+
+    /// Tries to remove the access control data corresponding to the database object.
+    ///
+    /// Returns `true` if no longer access control data exists for the database object. It is a
+    /// blocking and synchronous method, therefore this must be invoked in the background.
+    pub(super) fn try_remove_access_data_sync<C: Fn(&S::Instant) -> bool, D: FnMut(&S::Instant)>(
+        &self,
+        object_id: u64,
+        condition: &C,
+        deletion_notifier: &mut D,
+    ) -> bool {
         let mut found = false;
         let removed = self
             .table
@@ -852,9 +1941,78 @@ impl<S: Sequencer> AccessController<S> {
                 }
             })
             .is_some();
+        if removed {
+            self.record_entry_removed();
+        }
         !found || removed
     }
 
+    /// Transfers ownership of the database object to all the eligible waiting transactions.
+    ///
+    /// Returns `true` if there are waiting transactions.
+    fn transfer_ownership(object_state: &mut ObjectState<S>) -> bool {
+        object_state.prepare_ownership_transfer();
+        let wait_queue = if let ObjectState::Owned(ownership) = object_state {
+            match ownership {
+                Ownership::Created(_)
+                | Ownership::Protected(_)
+                | Ownership::Locked(_)
+                | Ownership::Deleted(_) => None,
+                Ownership::CreatedAwaitable(exclusive_awaitable)
+                | Ownership::LockedAwaitable(exclusive_awaitable)
+                | Ownership::DeletedAwaitable(exclusive_awaitable) => {
+                    let wait_queue = take(&mut exclusive_awaitable.wait_queue);
+                    Self::process_wait_queue(object_state, wait_queue)
+                }
+                Ownership::ProtectedAwaitable(shared_awaitable) => {
+                    let wait_queue = take(&mut shared_awaitable.wait_queue);
+                    Self::process_wait_queue(object_state, wait_queue)
+                }
+            }
+        } else {
+            None
+        };
+        Self::post_process_object_state(object_state, wait_queue)
+    }
+
+    /// Redirects the wait-for edges of the waiting transactions to the current owners.
+    ///
+    /// It has to be invoked whenever ownership of the database object is transferred while there
+    /// are waiting transactions, otherwise the edges would point at the previous owners.
+    fn refresh_wait_edges(&self, object_state: &ObjectState<S>) {
+        let (holders, wait_queue): (Vec<_>, _) = match object_state {
+            ObjectState::Owned(
+                Ownership::CreatedAwaitable(exclusive_awaitable)
+                | Ownership::LockedAwaitable(exclusive_awaitable)
+                | Ownership::DeletedAwaitable(exclusive_awaitable),
+            ) => (
+                vec![exclusive_awaitable.owner.transaction_serial()],
+                &exclusive_awaitable.wait_queue,
+            ),
+            ObjectState::Owned(Ownership::ProtectedAwaitable(shared_awaitable)) => (
+                shared_awaitable
+                    .owner_set
+                    .iter()
+                    .map(|o| o.transaction_serial())
+                    .collect(),
+                &shared_awaitable.wait_queue,
+            ),
+            _ => return,
+        };
+        for request in wait_queue.iter() {
+            let result_placeholder = match request {
+                Request::Create(_, _, result_placeholder)
+                | Request::Protect(_, _, result_placeholder)
+                | Request::Lock(_, _, result_placeholder)
+                | Request::Delete(_, _, result_placeholder) => result_placeholder,
+            };
+            if result_placeholder.is_pending() {
+                self.wait_for_graph
+                    .redirect(result_placeholder, holders.iter().copied());
+            }
+        }
+    }
+
     /// Processes the supplied wait queue.
     fn process_wait_queue(
         object_state: &mut ObjectState<S>,
@@ -923,6 +2081,52 @@ impl<S: Sequencer> AccessController<S> {
         }
     }
 
+    /// Grants the oldest waiting request as if no access control data existed.
+    ///
+    /// Returns the new [`ObjectState`] owned by the requester, or `None` if no request is pending.
+    fn grant_oldest_request(wait_queue: &mut WaitQueue<S>) -> Option<ObjectState<S>> {
+        while let Some(request) = wait_queue.clone_oldest() {
+            wait_queue.remove_oldest();
+            let (ownership, new_owner, result_placeholder) = match &request {
+                Request::Create(_, new_owner, result_placeholder) => (
+                    Ownership::Created(new_owner.clone()),
+                    new_owner,
+                    result_placeholder,
+                ),
+                Request::Protect(_, new_owner, result_placeholder) => (
+                    Ownership::Protected(new_owner.clone()),
+                    new_owner,
+                    result_placeholder,
+                ),
+                Request::Lock(_, new_owner, result_placeholder) => (
+                    Ownership::Locked(new_owner.clone()),
+                    new_owner,
+                    result_placeholder,
+                ),
+                Request::Delete(_, new_owner, result_placeholder) => (
+                    Ownership::Deleted(new_owner.clone()),
+                    new_owner,
+                    result_placeholder,
+                ),
+            };
+            let Some(mut result_waker) = result_placeholder.lock_sync() else {
+                // The `Mutex` was poisoned.
+                continue;
+            };
+            if result_waker.0.is_some() {
+                // The request was timed out.
+                continue;
+            }
+            result_waker.0.replace(Ok(true));
+            new_owner.set_wake_up_others();
+            if let Some(waker) = result_waker.1.take() {
+                waker.wake();
+            }
+            return Some(ObjectState::Owned(ownership));
+        }
+        None
+    }
+
     /// Cleans up [`ObjectState`] and gets the supplied wait queue into the [`ObjectState`].
     ///
     /// Returns `true` if there are waiting transactions.
@@ -1036,6 +2240,142 @@ impl<S: Sequencer> AccessController<S> {
         true
     }
 
+    /// Describes the ownership of the database object.
+    ///
+    /// Returns `None` if no active transactions own the database object.
+    fn lock_info(object_id: u64, object_state: &ObjectState<S>) -> Option<LockInfo> {
+        let ObjectState::Owned(ownership) = object_state else {
+            return None;
+        };
+        let (mode, owners) = match ownership {
+            Ownership::Created(owner) | Ownership::Locked(owner) | Ownership::Deleted(owner) => {
+                (LockMode::Exclusive, vec![owner])
+            }
+            Ownership::CreatedAwaitable(exclusive_awaitable)
+            | Ownership::LockedAwaitable(exclusive_awaitable)
+            | Ownership::DeletedAwaitable(exclusive_awaitable) => {
+                (LockMode::Exclusive, vec![&exclusive_awaitable.owner])
+            }
+            Ownership::Protected(owner) => (LockMode::Shared, vec![owner]),
+            Ownership::ProtectedAwaitable(shared_awaitable) => (
+                LockMode::Shared,
+                shared_awaitable.owner_set.iter().collect(),
+            ),
+        };
+        let mut owners: Vec<u64> = owners
+            .into_iter()
+            .filter(|o| !o.is_terminated())
+            .map(|o| o.transaction_id())
+            .collect();
+        if owners.is_empty() {
+            return None;
+        }
+        owners.sort_unstable();
+        owners.dedup();
+        Some(LockInfo {
+            object_id,
+            mode,
+            owners,
+        })
+    }
+
+    /// Records that access control data was inserted.
+    fn record_entry_inserted(&self) {
+        self.lock_count.fetch_add(1, Relaxed);
+        metrics::record_entry_inserted();
+    }
+
+    /// Records that access control data was removed.
+    fn record_entry_removed(&self) {
+        self.lock_count.fetch_sub(1, Relaxed);
+        metrics::record_entry_removed();
+    }
+
+    /// Checks if the journal is allowed to gain access to another database object.
+    fn check_lock_limit<P: PersistenceLayer<S>>(
+        &self,
+        journal: &Journal<'_, '_, S, P>,
+    ) -> Result<(), Error> {
+        let limit = self.max_locks_per_journal.load(Relaxed);
+        if limit != 0 && journal.lock_count() >= limit {
+            return Err(Error::LockLimitExceeded);
+        }
+        Ok(())
+    }
+
+    /// Gives up newly gained access to the database object if another transaction holds a table
+    /// lock covering it.
+    ///
+    /// If lock escalation is enabled, an intention lock on the table is acquired in order to
+    /// prevent other transactions from escalating their locks in the table.
+    async fn check_table_lock<P: PersistenceLayer<S>>(
+        &self,
+        object_id: u64,
+        journal: &Journal<'_, '_, S, P>,
+        result: Result<bool, Error>,
+    ) -> Result<bool, Error> {
+        if let Ok(true) = result {
+            let conflict = if let Some(lock_escalation_policy) = self.lock_escalation_policy() {
+                !self
+                    .range_locks
+                    .lock_intention(lock_escalation_policy.table(object_id), journal.anchor())
+            } else {
+                self.range_locks
+                    .is_exclusively_locked_by_others(object_id, journal.anchor())
+            };
+            if conflict {
+                self.revoke(object_id, journal.anchor()).await;
+                return Err(Error::Conflict);
+            }
+        }
+        result
+    }
+
+    /// Escalates the row locks of the journal to a table lock if the journal has newly gained
+    /// access to as many database objects in the table as the threshold.
+    ///
+    /// Access to the database object is given up if the table lock could not be acquired.
+    async fn escalate<P: PersistenceLayer<S>>(
+        &self,
+        object_id: u64,
+        journal: &mut Journal<'_, '_, S, P>,
+        releasable: bool,
+    ) -> Result<(), Error> {
+        let Some(lock_escalation_policy) = self.lock_escalation_policy() else {
+            return Ok(());
+        };
+        let table = lock_escalation_policy.table(object_id);
+        if self
+            .range_locks
+            .is_exclusively_locked_by(object_id, journal.anchor())
+        {
+            // The transaction holds the table lock.
+            return Ok(());
+        }
+        if journal.record_row(table.start, object_id, releasable) < lock_escalation_policy.threshold
+        {
+            return Ok(());
+        }
+
+        let anchor = journal.anchor().clone();
+        if !self.range_locks.lock_exclusive(table.clone(), &anchor) {
+            journal.forget_row(table.start, object_id);
+            if self.revoke(object_id, &anchor).await {
+                journal.forget_lock();
+            }
+            return Err(Error::LockLimitExceeded);
+        }
+
+        // The table lock covers the row locks, and therefore they can be released.
+        for (id, releasable) in journal.take_rows(table.start) {
+            if releasable && self.revoke(id, &anchor).await {
+                journal.forget_lock();
+            }
+        }
+        journal.record_lock();
+        Ok(())
+    }
+
     /// Tries to create the database object.
     ///
     /// Returns `Ok(None)` if the result will be out after waiting.
@@ -1086,6 +2426,8 @@ impl<S: Sequencer> AccessController<S> {
                         }
                         return Ok(None);
                     }
+                    // Another transaction is creating the database object.
+                    return Err(Error::Conflict);
                 }
             }
         }
@@ -1149,7 +2491,8 @@ impl<S: Sequencer> AccessController<S> {
                             } else if deadline.is_some() {
                                 return Ok(None);
                             }
-                            break;
+                            // Other transactions are waiting for the database object.
+                            return Err(Error::Conflict);
                         }
                     }
                 }
@@ -1162,8 +2505,8 @@ impl<S: Sequencer> AccessController<S> {
                     return Ok(Some(true));
                 }
                 ObjectState::Deleted(_) => {
-                    // Already deleted.
-                    break;
+                    // Already deleted by a committed transaction.
+                    return Err(Error::Conflict);
                 }
             }
         }
@@ -1197,7 +2540,8 @@ impl<S: Sequencer> AccessController<S> {
                         }
                     }
                     Ownership::Protected(owner) => {
-                        if let Relationship::Linearizable = owner.grant_write_access(new_owner) {
+                        let relationship = owner.grant_write_access(new_owner);
+                        if let Relationship::Linearizable = relationship {
                             // It is possible to promote the access permission in this transaction.
                             let mut exclusive_awaitable =
                                 ExclusiveAwaitable::with_owner(Owner::new(new_owner));
@@ -1213,6 +2557,9 @@ impl<S: Sequencer> AccessController<S> {
                                 SharedAwaitable::with_owner(owner.clone()).into(),
                             );
                             return Ok(None);
+                        } else if let Relationship::Unknown = relationship {
+                            // Another transaction is protecting the database object.
+                            return Err(Error::Conflict);
                         }
                         break;
                     }
@@ -1236,7 +2583,15 @@ impl<S: Sequencer> AccessController<S> {
                             *ownership = Ownership::LockedAwaitable(exclusive_awaitable);
                             return Ok(Some(true));
                         } else if deadline.is_some() {
+                            if shared_awaitable.is_promotion_deadlocked(new_owner) {
+                                // Another shared owner is waiting for the transaction to release
+                                // the shared lock.
+                                return Err(Error::Deadlock);
+                            }
                             return Ok(None);
+                        } else if shared_awaitable.is_shared_with_others(new_owner) {
+                            // Other transactions are protecting the database object.
+                            return Err(Error::Conflict);
                         }
                         break;
                     }
@@ -1249,8 +2604,8 @@ impl<S: Sequencer> AccessController<S> {
                     return Ok(Some(true));
                 }
                 ObjectState::Deleted(_) => {
-                    // Already deleted.
-                    break;
+                    // Already deleted by a committed transaction.
+                    return Err(Error::Conflict);
                 }
             }
         }
@@ -1284,7 +2639,8 @@ impl<S: Sequencer> AccessController<S> {
                         }
                     }
                     Ownership::Protected(owner) => {
-                        if let Relationship::Linearizable = owner.grant_write_access(new_owner) {
+                        let relationship = owner.grant_write_access(new_owner);
+                        if let Relationship::Linearizable = relationship {
                             // It is possible to promote the access permission in this transaction.
                             let mut exclusive_awaitable =
                                 ExclusiveAwaitable::with_owner(Owner::new(new_owner));
@@ -1301,6 +2657,9 @@ impl<S: Sequencer> AccessController<S> {
                                 SharedAwaitable::with_owner(owner.clone()).into(),
                             );
                             return Ok(None);
+                        } else if let Relationship::Unknown = relationship {
+                            // Another transaction is protecting the database object.
+                            return Err(Error::Conflict);
                         }
                         break;
                     }
@@ -1325,7 +2684,15 @@ impl<S: Sequencer> AccessController<S> {
                             *ownership = Ownership::DeletedAwaitable(exclusive_awaitable);
                             return Ok(Some(true));
                         } else if deadline.is_some() {
+                            if shared_awaitable.is_promotion_deadlocked(new_owner) {
+                                // Another shared owner is waiting for the transaction to release
+                                // the shared lock.
+                                return Err(Error::Deadlock);
+                            }
                             return Ok(None);
+                        } else if shared_awaitable.is_shared_with_others(new_owner) {
+                            // Other transactions are protecting the database object.
+                            return Err(Error::Conflict);
                         }
                         break;
                     }
@@ -1338,14 +2705,62 @@ impl<S: Sequencer> AccessController<S> {
                     return Ok(Some(true));
                 }
                 ObjectState::Deleted(_) => {
-                    // Already deleted.
-                    break;
+                    // Already deleted by a committed transaction.
+                    return Err(Error::Conflict);
                 }
             }
         }
         Err(Error::SerializationFailure)
     }
 
+    /// Tries to downgrade the exclusive lock on the database object to a shared lock.
+    fn try_downgrade(
+        object_state: &mut ObjectState<S>,
+        new_owner: &ebr::Arc<JournalAnchor<S>>,
+    ) -> Result<(), Error> {
+        object_state.prepare_ownership_transfer();
+        if let ObjectState::Owned(ownership) = object_state {
+            match ownership {
+                Ownership::Locked(owner) => {
+                    if let Relationship::Linearizable = owner.grant_write_access(new_owner) {
+                        *ownership = Ownership::Protected(owner.clone());
+                        return Ok(());
+                    }
+                }
+                Ownership::LockedAwaitable(exclusive_awaitable) => {
+                    if let Relationship::Linearizable =
+                        exclusive_awaitable.owner.grant_write_access(new_owner)
+                    {
+                        let mut shared_awaitable = SharedAwaitable::with_instant_and_owner(
+                            exclusive_awaitable.creation_instant,
+                            exclusive_awaitable.owner.clone(),
+                        );
+                        shared_awaitable.wait_queue = take(&mut exclusive_awaitable.wait_queue);
+
+                        // The shared owners before promotion keep their shared locks.
+                        match exclusive_awaitable.prior_ownership.take().map(|o| *o) {
+                            Some(Ownership::Protected(owner)) => {
+                                shared_awaitable.owner_set.insert(owner);
+                            }
+                            Some(Ownership::ProtectedAwaitable(mut prior_shared_awaitable)) => {
+                                shared_awaitable
+                                    .owner_set
+                                    .append(&mut prior_shared_awaitable.owner_set);
+                            }
+                            _ => (),
+                        }
+                        *ownership = Ownership::ProtectedAwaitable(shared_awaitable.into());
+                        return Ok(());
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        // The transaction does not exclusively lock the database object.
+        Err(Error::Conflict)
+    }
+
     /// Takes shared ownership of the exclusively owned database object.
     ///
     /// Returns `Ok(None)` if the result will be out after waiting.
@@ -1365,7 +2780,8 @@ impl<S: Sequencer> AccessController<S> {
         match owner.grant_write_access(new_owner) {
             Relationship::Committed(commit_instant) => {
                 if is_deleted {
-                    return Err(Error::SerializationFailure);
+                    // The database object was deleted by a committed transaction.
+                    return Err(Error::Conflict);
                 }
                 *ownership = if is_created {
                     Ownership::ProtectedAwaitable(
@@ -1400,7 +2816,7 @@ impl<S: Sequencer> AccessController<S> {
                     Ok(None)
                 } else {
                     // No deadline is specified.
-                    Err(Error::SerializationFailure)
+                    Err(Error::Conflict)
                 }
             }
         }
@@ -1422,7 +2838,8 @@ impl<S: Sequencer> AccessController<S> {
         match exclusive_awaitable.owner.grant_write_access(new_owner) {
             Relationship::Committed(commit_instant) => {
                 if is_deleted {
-                    return Err(Error::SerializationFailure);
+                    // The database object was deleted by a committed transaction.
+                    return Err(Error::Conflict);
                 } else if exclusive_awaitable.wait_queue.is_empty() {
                     // The transaction was committed and no transactions are waiting for the
                     // database object.
@@ -1467,7 +2884,7 @@ impl<S: Sequencer> AccessController<S> {
             Ok((false, true))
         } else {
             // No deadline is specified.
-            Err(Error::SerializationFailure)
+            Err(Error::Conflict)
         }
     }
 
@@ -1490,7 +2907,8 @@ impl<S: Sequencer> AccessController<S> {
         match owner.grant_write_access(new_owner) {
             Relationship::Committed(commit_instant) => {
                 if is_deleted {
-                    Err(Error::SerializationFailure)
+                    // The database object was deleted by a committed transaction.
+                    Err(Error::Conflict)
                 } else {
                     *ownership = if is_created {
                         Ownership::LockedAwaitable(
@@ -1526,7 +2944,7 @@ impl<S: Sequencer> AccessController<S> {
                     Ok(None)
                 } else {
                     // No deadline is specified.
-                    Err(Error::SerializationFailure)
+                    Err(Error::Conflict)
                 }
             }
         }
@@ -1548,7 +2966,8 @@ impl<S: Sequencer> AccessController<S> {
         match exclusive_awaitable.owner.grant_write_access(new_owner) {
             Relationship::Committed(commit_instant) => {
                 if is_deleted {
-                    return Err(Error::SerializationFailure);
+                    // The database object was deleted by a committed transaction.
+                    return Err(Error::Conflict);
                 } else if exclusive_awaitable.wait_queue.is_empty() {
                     // The transaction was committed and no transactions are waiting for the
                     // database object.
@@ -1593,7 +3012,7 @@ impl<S: Sequencer> AccessController<S> {
             Ok((false, true))
         } else {
             // No deadline is specified.
-            Err(Error::SerializationFailure)
+            Err(Error::Conflict)
         }
     }
 
@@ -1616,7 +3035,8 @@ impl<S: Sequencer> AccessController<S> {
         match owner.grant_write_access(new_owner) {
             Relationship::Committed(commit_instant) => {
                 if is_deleted {
-                    Err(Error::SerializationFailure)
+                    // The database object was deleted by a committed transaction.
+                    Err(Error::Conflict)
                 } else {
                     *ownership = if is_created {
                         Ownership::DeletedAwaitable(
@@ -1664,7 +3084,7 @@ impl<S: Sequencer> AccessController<S> {
                     Ok(None)
                 } else {
                     // No deadline is specified.
-                    Err(Error::SerializationFailure)
+                    Err(Error::Conflict)
                 }
             }
         }
@@ -1686,7 +3106,8 @@ impl<S: Sequencer> AccessController<S> {
         match exclusive_awaitable.owner.grant_write_access(new_owner) {
             Relationship::Committed(commit_instant) => {
                 if is_deleted {
-                    return Err(Error::SerializationFailure);
+                    // The database object was deleted by a committed transaction.
+                    return Err(Error::Conflict);
                 } else if exclusive_awaitable.wait_queue.is_empty() {
                     // The transaction was committed and no transactions are waiting for the
                     // database object.
@@ -1753,7 +3174,7 @@ impl<S: Sequencer> AccessController<S> {
             Ok((false, true))
         } else {
             // No deadline is specified.
-            Err(Error::SerializationFailure)
+            Err(Error::Conflict)
         }
     }
 
@@ -1784,6 +3205,41 @@ impl<S: Sequencer> AccessController<S> {
     }
 }
 
+impl LockEscalationPolicy {
+    /// Returns the range of database object identifiers in the table containing the database
+    /// object.
+    fn table(&self, object_id: u64) -> Range<u64> {
+        let mask = u64::MAX
+            .checked_shr(64 - self.row_bits.min(64))
+            .unwrap_or(0);
+        let start = object_id & !mask;
+        start..start.saturating_add(mask).saturating_add(1)
+    }
+}
+
+impl LockInfo {
+    /// Returns the identifier of the database object.
+    #[inline]
+    #[must_use]
+    pub fn object_id(&self) -> u64 {
+        self.object_id
+    }
+
+    /// Returns the lock mode.
+    #[inline]
+    #[must_use]
+    pub fn mode(&self) -> LockMode {
+        self.mode
+    }
+
+    /// Returns the identifiers of the transactions owning the database object.
+    #[inline]
+    #[must_use]
+    pub fn owners(&self) -> &[u64] {
+        &self.owners
+    }
+}
+
 impl<S: Sequencer> Owner<S> {
     /// Creates a new [`Owner`].
     fn new(anchor: &ebr::Arc<JournalAnchor<S>>) -> Owner<S> {
@@ -2039,10 +3495,41 @@ impl<S: Sequencer> SharedAwaitable<S> {
         Some(exclusive_awaitable.into())
     }
 
-    /// Cleans up committed and rolled back owners.
+    /// Checks if the shared owner will never be able to promote its access permission.
     ///
-    /// Returns `true` if the [`SharedAwaitable`] got totally empty.
-    fn cleanup_inactive_owners(&mut self) -> bool {
+    /// Returns `true` if the requester shares the database object, and another transaction
+    /// sharing the database object is already waiting for exclusive access to it.
+    fn is_promotion_deadlocked(&self, new_owner: &ebr::Arc<JournalAnchor<S>>) -> bool {
+        let is_shared_owner = |anchor: &JournalAnchor<S>| {
+            self.owner_set
+                .iter()
+                .any(|o| matches!(o.grant_write_access(anchor), Relationship::Linearizable))
+        };
+        if !is_shared_owner(&**new_owner) {
+            return false;
+        }
+        self.wait_queue.iter().any(|r| match r {
+            Request::Lock(_, waiting_owner, result_placeholder)
+            | Request::Delete(_, waiting_owner, result_placeholder) => {
+                waiting_owner.transaction_id() != new_owner.transaction_id()
+                    && result_placeholder.is_pending()
+                    && is_shared_owner(&**waiting_owner)
+            }
+            Request::Create(..) | Request::Protect(..) => false,
+        })
+    }
+
+    /// Checks if any other transaction is protecting the database object.
+    fn is_shared_with_others(&self, new_owner: &ebr::Arc<JournalAnchor<S>>) -> bool {
+        self.owner_set
+            .iter()
+            .any(|o| matches!(o.grant_write_access(new_owner), Relationship::Unknown))
+    }
+
+    /// Cleans up committed and rolled back owners.
+    ///
+    /// Returns `true` if the [`SharedAwaitable`] got totally empty.
+    fn cleanup_inactive_owners(&mut self) -> bool {
         self.owner_set.retain(|o| {
             // Delete the entry if the owner definitely does not hold the shared ownership.
             !o.is_terminated()
@@ -2053,6 +3540,7 @@ impl<S: Sequencer> SharedAwaitable<S> {
     /// Pushes a request into the wait queue.
     fn push_request(&mut self, request: Request<S>) {
         self.wait_queue.push_back(request);
+        metrics::record_wait_queue_depth(self.wait_queue.len());
         self.owner_set.iter().for_each(|o| {
             o.set_wake_up_others();
         });
@@ -2132,6 +3620,7 @@ impl<S: Sequencer> ExclusiveAwaitable<S> {
     /// Pushes a request into the wait queue.
     fn push_request(&mut self, request: Request<S>) {
         self.wait_queue.push_back(request);
+        metrics::record_wait_queue_depth(self.wait_queue.len());
         self.owner.set_wake_up_others();
     }
 }
@@ -2211,12 +3700,15 @@ impl<S: Sequencer> Drop for WaitQueue<S> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::task_processor::Task;
     use crate::{Database, MonotonicU64};
+    use std::future::poll_fn;
     use std::num::NonZeroU32;
     use std::path::Path;
     use std::sync::atomic::AtomicUsize;
     use std::sync::atomic::Ordering::Relaxed;
     use std::sync::Arc;
+    use std::task::Poll;
     use std::time::Duration;
     use tokio::fs::remove_dir_all;
     use tokio::sync::Barrier;
@@ -2226,6 +3718,60 @@ mod tests {
     const TIMEOUT_UNEXPECTED: Duration = Duration::from_secs(60);
     const TIMEOUT_EXPECTED: Duration = Duration::from_millis(1);
 
+    /// Waits until the task processor has completed an iteration that started after the call.
+    ///
+    /// The task processor wakes up the [`Task::WakeUp`] wakers when it receives them at the
+    /// beginning of an iteration, therefore the second wake-up happens after the iteration that
+    /// received the first one has been completed.
+    async fn wait_for_task_processor<S: Sequencer, P: PersistenceLayer<S>>(
+        database: &Database<S, P>,
+    ) {
+        for _ in 0..2 {
+            let mut sent = false;
+            poll_fn(|cx| {
+                if sent {
+                    return Poll::Ready(());
+                }
+                sent = database
+                    .task_processor()
+                    .send_task(Task::WakeUp(Instant::now(), cx.waker().clone()));
+                if !sent {
+                    // The message channel is congested.
+                    cx.waker().wake_by_ref();
+                }
+                Poll::Pending
+            })
+            .await;
+        }
+    }
+
+    /// Waits until the number of access requests waiting for the database object reaches `len`.
+    async fn wait_for_requests<S: Sequencer>(
+        access_controller: &AccessController<S>,
+        object_id: u64,
+        len: usize,
+    ) {
+        while access_controller
+            .table
+            .read_async(&object_id, |_, object_state| match object_state {
+                ObjectState::Owned(
+                    Ownership::CreatedAwaitable(exclusive_awaitable)
+                    | Ownership::LockedAwaitable(exclusive_awaitable)
+                    | Ownership::DeletedAwaitable(exclusive_awaitable),
+                ) => exclusive_awaitable.wait_queue.len(),
+                ObjectState::Owned(Ownership::ProtectedAwaitable(shared_awaitable)) => {
+                    shared_awaitable.wait_queue.len()
+                }
+                _ => 0,
+            })
+            .await
+            .unwrap_or(0)
+            != len
+        {
+            tokio::task::yield_now().await;
+        }
+    }
+
     #[derive(Clone, Copy, Debug, Eq, PartialEq)]
     enum TransactionAction {
         Commit,
@@ -2417,119 +3963,1360 @@ mod tests {
                 }
                 assert!(remove_dir_all(path).await.is_ok());
             }
-        }
+        }
+    }
+
+    #[tokio::test]
+    async fn access_promote_wait() {
+        for num_shared_locks in 1..3 {
+            for block in [AccessAction::Lock, AccessAction::Delete] {
+                for promotion in [AccessAction::Lock, AccessAction::Delete] {
+                    const DIR: &str = "access_controller_promote_wait_test";
+                    let path = Path::new(DIR);
+                    let database = Database::with_path(path).await.unwrap();
+                    let access_controller = database.access_controller();
+                    let transaction = database.transaction();
+                    for i in 0..num_shared_locks {
+                        let mut journal = transaction.journal();
+                        assert_eq!(
+                            take_access_action(
+                                AccessAction::Share,
+                                access_controller,
+                                &mut journal,
+                                None
+                            )
+                            .await,
+                            Ok(i == 0)
+                        );
+                        assert_eq!(Some(journal.submit()), NonZeroU32::new(i + 1));
+                    }
+                    let blocker_transaction = database.transaction();
+                    let mut blocker_journal = blocker_transaction.journal();
+                    let mut journal = transaction.journal();
+                    let (blocker_result, result) = futures::join!(
+                        take_access_action(
+                            block,
+                            access_controller,
+                            &mut blocker_journal,
+                            Some(Instant::now() + TIMEOUT_EXPECTED)
+                        ),
+                        take_access_action(
+                            promotion,
+                            access_controller,
+                            &mut journal,
+                            Some(Instant::now() + TIMEOUT_UNEXPECTED)
+                        )
+                    );
+                    assert_eq!(result, Ok(true));
+                    assert_eq!(blocker_result, Err(Error::Timeout));
+                    assert!(remove_dir_all(path).await.is_ok());
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn access_promote_commit() {
+        for num_shared_locks in 1..3 {
+            for waiting_action in [AccessAction::Lock, AccessAction::Delete] {
+                for promotion in [AccessAction::Lock, AccessAction::Delete] {
+                    const DIR: &str = "access_controller_promote_commit_test";
+                    let path = Path::new(DIR);
+                    let database = Database::with_path(path).await.unwrap();
+                    let access_controller = database.access_controller();
+                    let transaction = database.transaction();
+                    for i in 0..num_shared_locks {
+                        let mut journal = transaction.journal();
+                        assert_eq!(
+                            take_access_action(
+                                AccessAction::Share,
+                                access_controller,
+                                &mut journal,
+                                None
+                            )
+                            .await,
+                            Ok(i == 0)
+                        );
+                        assert_eq!(Some(journal.submit()), NonZeroU32::new(i + 1));
+                    }
+                    let waiting_transaction = database.transaction();
+                    let mut waiting_journal = waiting_transaction.journal();
+                    let (waiting_result, _) = futures::join!(
+                        take_access_action(
+                            waiting_action,
+                            access_controller,
+                            &mut waiting_journal,
+                            Some(Instant::now() + TIMEOUT_UNEXPECTED)
+                        ),
+                        async {
+                            let mut journal = transaction.journal();
+                            assert_eq!(
+                                take_access_action(
+                                    promotion,
+                                    access_controller,
+                                    &mut journal,
+                                    Some(Instant::now() + TIMEOUT_UNEXPECTED),
+                                )
+                                .await,
+                                Ok(true)
+                            );
+                            assert_eq!(
+                                Some(journal.submit()),
+                                NonZeroU32::new(num_shared_locks + 1)
+                            );
+                            assert!(transaction.commit().await.is_ok());
+                        }
+                    );
+                    if promotion == AccessAction::Delete {
+                        assert_eq!(waiting_result, Err(Error::Conflict));
+                    } else {
+                        assert_eq!(waiting_result, Ok(true));
+                    }
+                    assert!(remove_dir_all(path).await.is_ok());
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn access_share_conflict() {
+        for promotion in [AccessAction::Lock, AccessAction::Delete] {
+            const DIR: &str = "access_controller_share_conflict_test";
+            let path = Path::new(DIR);
+            let database = Database::with_path(path).await.unwrap();
+            let access_controller = database.access_controller();
+            let transaction = database.transaction();
+            let other_transaction = database.transaction();
+            for t in [&transaction, &other_transaction] {
+                let mut journal = t.journal();
+                assert_eq!(
+                    take_access_action(AccessAction::Share, access_controller, &mut journal, None)
+                        .await,
+                    Ok(true)
+                );
+                assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+            }
+
+            let mut journal = transaction.journal();
+            assert_eq!(
+                take_access_action(promotion, access_controller, &mut journal, None).await,
+                Err(Error::Conflict)
+            );
+            assert_eq!(
+                take_access_action(
+                    promotion,
+                    access_controller,
+                    &mut journal,
+                    Some(Instant::now() + TIMEOUT_EXPECTED)
+                )
+                .await,
+                Err(Error::Timeout)
+            );
+
+            assert!(other_transaction.commit().await.is_ok());
+            assert_eq!(
+                take_access_action(promotion, access_controller, &mut journal, None).await,
+                Ok(true)
+            );
+            assert_eq!(Some(journal.submit()), NonZeroU32::new(2));
+
+            let other_transaction = database.transaction();
+            let mut other_journal = other_transaction.journal();
+            assert_eq!(
+                take_access_action(
+                    AccessAction::Share,
+                    access_controller,
+                    &mut other_journal,
+                    None
+                )
+                .await,
+                Err(Error::Conflict)
+            );
+            drop(other_journal);
+            drop(other_transaction);
+            drop(transaction);
+            assert!(remove_dir_all(path).await.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn access_promote_deadlock() {
+        for promotion in [AccessAction::Lock, AccessAction::Delete] {
+            const DIR: &str = "access_controller_promote_deadlock_test";
+            let path = Path::new(DIR);
+            let database = Database::with_path(path).await.unwrap();
+            let access_controller = database.access_controller();
+            let transaction = database.transaction();
+            let other_transaction = database.transaction();
+            for t in [&transaction, &other_transaction] {
+                let mut journal = t.journal();
+                assert_eq!(
+                    take_access_action(AccessAction::Share, access_controller, &mut journal, None)
+                        .await,
+                    Ok(true)
+                );
+                assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+            }
+
+            let mut journal = transaction.journal();
+            let (result, other_result) = futures::join!(
+                take_access_action(
+                    promotion,
+                    access_controller,
+                    &mut journal,
+                    Some(Instant::now() + TIMEOUT_UNEXPECTED)
+                ),
+                async move {
+                    let mut other_journal = other_transaction.journal();
+                    let result = take_access_action(
+                        promotion,
+                        access_controller,
+                        &mut other_journal,
+                        Some(Instant::now() + TIMEOUT_UNEXPECTED),
+                    )
+                    .await;
+                    drop(other_journal);
+                    other_transaction.rollback();
+                    result
+                }
+            );
+            assert_eq!(result, Ok(true));
+            assert_eq!(other_result, Err(Error::Deadlock));
+            assert_eq!(Some(journal.submit()), NonZeroU32::new(2));
+            assert!(transaction.commit().await.is_ok());
+            assert!(remove_dir_all(path).await.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn access_wait_for_deadlock() {
+        const DIR: &str = "access_controller_wait_for_deadlock_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        let transaction = database.transaction();
+        let other_transaction = database.transaction();
+        for (object_id, t) in [(0, &transaction), (1, &other_transaction)] {
+            let mut journal = t.journal();
+            assert_eq!(
+                access_controller.lock(object_id, &mut journal, None).await,
+                Ok(true)
+            );
+            assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+        }
+
+        let mut journal = transaction.journal();
+        let (result, other_result) = futures::join!(
+            access_controller.lock(1, &mut journal, Some(Instant::now() + TIMEOUT_UNEXPECTED)),
+            async move {
+                let mut other_journal = other_transaction.journal();
+                let result = access_controller
+                    .lock(
+                        0,
+                        &mut other_journal,
+                        Some(Instant::now() + TIMEOUT_UNEXPECTED),
+                    )
+                    .await;
+                drop(other_journal);
+                other_transaction.rollback();
+                result
+            }
+        );
+        assert_eq!(result, Ok(true));
+        assert_eq!(other_result, Err(Error::Deadlock));
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(2));
+        assert!(transaction.commit().await.is_ok());
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn access_wait_for_redirect() {
+        const DIR: &str = "access_controller_wait_for_redirect_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        let transaction = database.transaction();
+        let waiting_transaction = database.transaction();
+        let other_waiting_transaction = database.transaction();
+        for (object_id, t) in [(0, &transaction), (2, &other_waiting_transaction)] {
+            let mut journal = t.journal();
+            assert_eq!(
+                access_controller.lock(object_id, &mut journal, None).await,
+                Ok(true)
+            );
+            assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+        }
+
+        // The wait-for edge of the other waiting transaction is redirected to the waiting
+        // transaction when it takes ownership of the database object, and the other waiting
+        // transaction is chosen as the victim since it is younger.
+        let (result, other_result, _) = futures::join!(
+            async move {
+                let mut journal = waiting_transaction.journal();
+                let result = access_controller
+                    .lock(0, &mut journal, Some(Instant::now() + TIMEOUT_UNEXPECTED))
+                    .await;
+                let other_result = access_controller
+                    .lock(2, &mut journal, Some(Instant::now() + TIMEOUT_UNEXPECTED))
+                    .await;
+                assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+                assert!(waiting_transaction.commit().await.is_ok());
+                (result, other_result)
+            },
+            async move {
+                wait_for_requests(access_controller, 0, 1).await;
+                let mut journal = other_waiting_transaction.journal();
+                let result = access_controller
+                    .lock(0, &mut journal, Some(Instant::now() + TIMEOUT_UNEXPECTED))
+                    .await;
+                drop(journal);
+                other_waiting_transaction.rollback();
+                result
+            },
+            async move {
+                wait_for_requests(access_controller, 0, 2).await;
+                assert!(transaction.commit().await.is_ok());
+            }
+        );
+        assert_eq!(result, (Ok(true), Ok(true)));
+        assert_eq!(other_result, Err(Error::Deadlock));
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn access_wait_for_timeout() {
+        const DIR: &str = "access_controller_wait_for_timeout_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        let transaction = database.transaction();
+        let other_transaction = database.transaction();
+        for (object_id, t) in [(0, &transaction), (1, &other_transaction)] {
+            let mut journal = t.journal();
+            assert_eq!(
+                access_controller.lock(object_id, &mut journal, None).await,
+                Ok(true)
+            );
+            assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+        }
+
+        // The wait-for edges are removed after the deadline is reached.
+        for (object_id, t) in [(1, &transaction), (0, &other_transaction)] {
+            let mut journal = t.journal();
+            assert_eq!(
+                access_controller
+                    .lock(
+                        object_id,
+                        &mut journal,
+                        Some(Instant::now() + TIMEOUT_EXPECTED)
+                    )
+                    .await,
+                Err(Error::Timeout)
+            );
+        }
+        assert!(transaction.commit().await.is_ok());
+        assert!(other_transaction.commit().await.is_ok());
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn access_downgrade() {
+        const DIR: &str = "access_controller_downgrade_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        let transaction = database.transaction();
+        let mut journal = transaction.journal();
+        assert_eq!(
+            take_access_action(AccessAction::Lock, access_controller, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+
+        let other_transaction = database.transaction();
+        let mut other_journal = other_transaction.journal();
+        assert_eq!(
+            access_controller.downgrade(0, &mut other_journal).await,
+            Err(Error::Conflict)
+        );
+        assert_eq!(
+            access_controller.downgrade(1, &mut other_journal).await,
+            Err(Error::Conflict)
+        );
+        drop(other_journal);
+
+        let readers = [
+            database.transaction(),
+            database.transaction(),
+            database.transaction(),
+        ];
+        let mut reader_journals = [
+            readers[0].journal(),
+            readers[1].journal(),
+            readers[2].journal(),
+        ];
+        let [first, second, third] = &mut reader_journals;
+        let mut journal = transaction.journal();
+        let (first_result, second_result, third_result, downgrade_result) = futures::join!(
+            take_access_action(
+                AccessAction::Share,
+                access_controller,
+                first,
+                Some(Instant::now() + TIMEOUT_UNEXPECTED)
+            ),
+            take_access_action(
+                AccessAction::Share,
+                access_controller,
+                second,
+                Some(Instant::now() + TIMEOUT_UNEXPECTED)
+            ),
+            take_access_action(
+                AccessAction::Share,
+                access_controller,
+                third,
+                Some(Instant::now() + TIMEOUT_UNEXPECTED)
+            ),
+            access_controller.downgrade(0, &mut journal)
+        );
+        assert_eq!(first_result, Ok(true));
+        assert_eq!(second_result, Ok(true));
+        assert_eq!(third_result, Ok(true));
+        assert_eq!(downgrade_result, Ok(()));
+        assert_eq!(
+            access_controller.downgrade(0, &mut journal).await,
+            Err(Error::Conflict)
+        );
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(2));
+
+        // The downgraded transaction still protects the database object.
+        let mut other_journal = other_transaction.journal();
+        assert_eq!(
+            take_access_action(
+                AccessAction::Lock,
+                access_controller,
+                &mut other_journal,
+                None
+            )
+            .await,
+            Err(Error::Conflict)
+        );
+        drop(other_journal);
+        drop(reader_journals);
+        for reader in readers {
+            assert!(reader.commit().await.is_ok());
+        }
+        assert!(transaction.commit().await.is_ok());
+        assert!(other_transaction.commit().await.is_ok());
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn access_lock_for() {
+        const DIR: &str = "access_controller_lock_for_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        let transaction = database.transaction();
+        let mut journal = transaction.journal();
+        assert_eq!(
+            access_controller
+                .lock_for(0, &mut journal, LockMode::Exclusive, Duration::ZERO)
+                .await,
+            Ok(true)
+        );
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+
+        for mode in [LockMode::Shared, LockMode::Exclusive] {
+            let other_transaction = database.transaction();
+            let mut other_journal = other_transaction.journal();
+            assert_eq!(
+                access_controller
+                    .lock_for(0, &mut other_journal, mode, Duration::ZERO)
+                    .await,
+                Err(Error::Conflict)
+            );
+            let instant = Instant::now();
+            assert_eq!(
+                access_controller
+                    .lock_for(0, &mut other_journal, mode, Duration::from_millis(50))
+                    .await,
+                Err(Error::Timeout)
+            );
+            assert!(instant.elapsed() >= Duration::from_millis(50));
+        }
+        assert!(transaction.commit().await.is_ok());
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn access_savepoint() {
+        const DIR: &str = "access_controller_savepoint_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        let mut transaction = database.transaction();
+        let mut journal = transaction.journal();
+        assert_eq!(
+            access_controller.share(0, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(
+            access_controller.lock(1, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+
+        let savepoint = transaction.savepoint();
+        let mut journal = transaction.journal();
+        assert_eq!(
+            access_controller.lock(0, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(
+            access_controller.lock(1, &mut journal, None).await,
+            Ok(false)
+        );
+        assert_eq!(
+            access_controller.create(2, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(2));
+        let snapshot = transaction.snapshot();
+        assert_eq!(access_controller.read(2, &snapshot, None).await, Ok(true));
+        drop(snapshot);
+
+        assert_eq!(transaction.rollback_to(&savepoint), Ok(NonZeroU32::new(1)));
+        let snapshot = transaction.snapshot();
+        assert_eq!(access_controller.read(2, &snapshot, None).await, Ok(false));
+        drop(snapshot);
+
+        // Locks acquired before the savepoint are kept.
+        let other_transaction = database.transaction();
+        let mut other_journal = other_transaction.journal();
+        assert_eq!(
+            access_controller.share(0, &mut other_journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(
+            access_controller.lock(1, &mut other_journal, None).await,
+            Err(Error::Conflict)
+        );
+        assert_eq!(
+            access_controller.create(2, &mut other_journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(Some(other_journal.submit()), NonZeroU32::new(1));
+        assert!(other_transaction.commit().await.is_ok());
+        assert!(transaction.commit().await.is_ok());
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn read_snapshot_too_old() {
+        const DIR: &str = "access_controller_read_snapshot_too_old_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        let old_snapshot = database.snapshot();
+        let transaction = database.transaction();
+        let mut journal = transaction.journal();
+        assert_eq!(
+            access_controller.create(0, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+        let commit_instant = transaction.commit().await.unwrap();
+
+        assert_eq!(
+            access_controller.read(0, &old_snapshot, None).await,
+            Ok(false)
+        );
+        assert_eq!(
+            access_controller.advance_gc_horizon(commit_instant),
+            commit_instant
+        );
+        assert_eq!(
+            access_controller.advance_gc_horizon(Default::default()),
+            commit_instant
+        );
+        assert_eq!(
+            access_controller.read(0, &old_snapshot, None).await,
+            Err(Error::SnapshotTooOld)
+        );
+        drop(old_snapshot);
+
+        let snapshot = database.snapshot();
+        assert_eq!(access_controller.read(0, &snapshot, None).await, Ok(true));
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn gc_horizon() {
+        const DIR: &str = "access_controller_gc_horizon_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        let old_snapshot = database.snapshot();
+        let mut commit_instant = Default::default();
+        for object_id in 0..2 {
+            let transaction = database.transaction();
+            let mut journal = transaction.journal();
+            assert_eq!(
+                access_controller
+                    .create(object_id, &mut journal, None)
+                    .await,
+                Ok(true)
+            );
+            assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+            commit_instant = transaction.commit().await.unwrap();
+        }
+
+        // The garbage collection horizon does not pass the oldest live snapshot.
+        wait_for_task_processor(&database).await;
+        assert!(access_controller.gc_horizon() < commit_instant);
+        assert_eq!(
+            access_controller.read(0, &old_snapshot, None).await,
+            Ok(false)
+        );
+        drop(old_snapshot);
+
+        // The garbage collection horizon follows the oldest live snapshot.
+        let transaction = database.transaction();
+        let mut journal = transaction.journal();
+        assert_eq!(
+            access_controller.create(2, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+        assert!(transaction.commit().await.is_ok());
+        wait_for_task_processor(&database).await;
+        assert!(access_controller.gc_horizon() >= commit_instant);
+        let snapshot = database.snapshot();
+        assert_eq!(access_controller.read(0, &snapshot, None).await, Ok(true));
+        drop(snapshot);
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn delete_read() {
+        const DIR: &str = "access_controller_delete_read_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        let transaction = database.transaction();
+        let mut journal = transaction.journal();
+        assert_eq!(
+            access_controller.create(0, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+        assert!(transaction.commit().await.is_ok());
+
+        for commit in [false, true] {
+            let transaction = database.transaction();
+            let mut journal = transaction.journal();
+            assert_eq!(
+                access_controller.delete(0, &mut journal, None).await,
+                Ok(true)
+            );
+            let journal_snapshot = journal.snapshot();
+            assert_eq!(
+                access_controller.read(0, &journal_snapshot, None).await,
+                Ok(false)
+            );
+            drop(journal_snapshot);
+            let snapshot = database.snapshot();
+            assert_eq!(access_controller.read(0, &snapshot, None).await, Ok(true));
+            drop(snapshot);
+            assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+            if commit {
+                assert!(transaction.commit().await.is_ok());
+            } else {
+                // Rolling back the transaction makes the database object visible again.
+                transaction.rollback();
+            }
+            let snapshot = database.snapshot();
+            assert_eq!(
+                access_controller.read(0, &snapshot, None).await,
+                Ok(!commit)
+            );
+        }
+
+        let transaction = database.transaction();
+        let mut journal = transaction.journal();
+        assert_eq!(
+            access_controller.delete(0, &mut journal, None).await,
+            Err(Error::Conflict)
+        );
+        assert_eq!(
+            access_controller
+                .delete(0, &mut journal, Some(Instant::now() + TIMEOUT_UNEXPECTED))
+                .await,
+            Err(Error::Conflict)
+        );
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn lock_limit() {
+        const DIR: &str = "access_controller_lock_limit_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        access_controller.set_max_locks_per_journal(NonZeroUsize::new(2));
+        assert_eq!(
+            access_controller.max_locks_per_journal(),
+            NonZeroUsize::new(2)
+        );
+
+        let transaction = database.transaction();
+        let mut journal = transaction.journal();
+        assert_eq!(
+            access_controller.lock(1, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(
+            access_controller.lock(1, &mut journal, None).await,
+            Ok(false)
+        );
+        assert_eq!(
+            access_controller.create(2, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(journal.lock_count(), 2);
+        assert_eq!(
+            access_controller.share(3, &mut journal, None).await,
+            Err(Error::LockLimitExceeded)
+        );
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+
+        let mut journal = transaction.journal();
+        assert_eq!(
+            access_controller.share(3, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(journal.lock_count(), 1);
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(2));
+
+        access_controller.set_max_locks_per_journal(None);
+        let mut journal = transaction.journal();
+        for object_id in 4..8 {
+            assert_eq!(
+                access_controller.lock(object_id, &mut journal, None).await,
+                Ok(true)
+            );
+        }
+        assert_eq!(journal.lock_count(), 4);
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(3));
+        assert!(transaction.commit().await.is_ok());
+
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn lock_escalation() {
+        const DIR: &str = "access_controller_lock_escalation_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        access_controller.set_lock_escalation_policy(Some(LockEscalationPolicy {
+            threshold: 3,
+            row_bits: 8,
+        }));
+
+        let other_transaction = database.transaction();
+        let mut other_journal = other_transaction.journal();
+        assert_eq!(
+            access_controller
+                .share(0x205, &mut other_journal, None)
+                .await,
+            Ok(true)
+        );
+
+        let transaction = database.transaction();
+        let mut journal = transaction.journal();
+        assert_eq!(
+            access_controller.create(0x101, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(
+            access_controller.lock(0x102, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(
+            access_controller.lock(0x103, &mut journal, None).await,
+            Ok(true)
+        );
+
+        // The row locks were released, whereas the created database object is still owned.
+        assert_eq!(journal.lock_count(), 2);
+        assert_eq!(access_controller.lock_count(), 2);
+        assert_eq!(
+            access_controller.lock(0x104, &mut journal, None).await,
+            Ok(false)
+        );
+        assert_eq!(
+            access_controller.share(0x110, &mut journal, None).await,
+            Ok(false)
+        );
+
+        assert_eq!(
+            access_controller
+                .lock(0x150, &mut other_journal, None)
+                .await,
+            Err(Error::Conflict)
+        );
+        assert_eq!(
+            access_controller
+                .create(0x151, &mut other_journal, None)
+                .await,
+            Err(Error::Conflict)
+        );
+        assert_eq!(
+            access_controller
+                .share(0x102, &mut other_journal, None)
+                .await,
+            Err(Error::Conflict)
+        );
+        assert_eq!(
+            access_controller.lock_range(0x1f0..0x210, &mut other_journal),
+            Err(Error::Conflict)
+        );
+        assert!(access_controller
+            .lock_range(0x200..0x210, &mut other_journal)
+            .is_ok());
+        assert_eq!(other_journal.lock_count(), 1);
+        assert_eq!(access_controller.lock_count(), 2);
+
+        // The other transaction shares a database object in the table.
+        assert_eq!(
+            access_controller.lock(0x201, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(
+            access_controller.lock(0x202, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(
+            access_controller.lock(0x203, &mut journal, None).await,
+            Err(Error::LockLimitExceeded)
+        );
+        assert_eq!(journal.lock_count(), 4);
+        assert_eq!(access_controller.lock_count(), 4);
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+        assert!(transaction.commit().await.is_ok());
+
+        assert_eq!(
+            access_controller
+                .lock(0x150, &mut other_journal, None)
+                .await,
+            Ok(true)
+        );
+        assert_eq!(Some(other_journal.submit()), NonZeroU32::new(1));
+        assert!(other_transaction.commit().await.is_ok());
+
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[cfg(feature = "statistics")]
+    #[tokio::test]
+    async fn statistics() {
+        const DIR: &str = "access_controller_statistics_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+
+        let transaction = database.transaction();
+        let mut journal = transaction.journal();
+        assert_eq!(access_controller.statistics(), Statistics::default());
+        for object_id in [1, 2] {
+            assert_eq!(
+                access_controller.lock(object_id, &mut journal, None).await,
+                Ok(true)
+            );
+        }
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+
+        let contender = database.transaction();
+        for _ in 0..2 {
+            let mut contender_journal = contender.journal();
+            assert_eq!(
+                access_controller
+                    .lock(
+                        1,
+                        &mut contender_journal,
+                        Some(Instant::now() + TIMEOUT_EXPECTED)
+                    )
+                    .await,
+                Err(Error::Timeout)
+            );
+        }
+        let mut contender_journal = contender.journal();
+        assert_eq!(
+            access_controller
+                .lock(2, &mut contender_journal, None)
+                .await,
+            Err(Error::Conflict)
+        );
+
+        let statistics = access_controller.statistics();
+        assert_eq!(statistics.acquisitions(), 2);
+        assert_eq!(statistics.immediate_grants(), 2);
+        assert_eq!(statistics.waits(), 2);
+        assert_eq!(statistics.wait_time_histogram().iter().sum::<u64>(), 2);
+        assert_eq!(statistics.conflicts(), 1);
+        assert_eq!(statistics.deadlocks(), 0);
+        assert_eq!(access_controller.top_contended(1), vec![(1, 2)]);
+        assert_eq!(access_controller.top_contended(4).len(), 2);
+
+        access_controller.reset_statistics();
+        assert_eq!(access_controller.statistics(), Statistics::default());
+        assert!(access_controller.top_contended(4).is_empty());
+
+        drop(contender_journal);
+        contender.rollback();
+        assert!(transaction.commit().await.is_ok());
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn cancel() {
+        const DIR: &str = "access_controller_cancel_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+
+        let owner = database.transaction();
+        let mut owner_journal = owner.journal();
+        assert_eq!(
+            access_controller.lock(1, &mut owner_journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(Some(owner_journal.submit()), NonZeroU32::new(1));
+
+        let waiter = database.transaction();
+        let mut waiter_journal = waiter.journal();
+        assert_eq!(
+            access_controller.lock(2, &mut waiter_journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(Some(waiter_journal.submit()), NonZeroU32::new(1));
+
+        let token = waiter.cancellation_token();
+        let mut waiter_journal = waiter.journal();
+        let (result, ()) = futures::join!(
+            access_controller.lock(
+                1,
+                &mut waiter_journal,
+                Some(Instant::now() + TIMEOUT_UNEXPECTED)
+            ),
+            async {
+                wait_for_requests(access_controller, 1, 1).await;
+                token.cancel();
+            }
+        );
+        assert_eq!(result, Err(Error::Cancelled));
+        drop(waiter_journal);
+
+        // Cancellation does not roll back submitted journals.
+        let reader = database.transaction();
+        let mut reader_journal = reader.journal();
+        assert_eq!(
+            access_controller.share(2, &mut reader_journal, None).await,
+            Err(Error::Conflict)
+        );
+
+        assert!(owner.commit().await.is_ok());
+        assert_eq!(
+            access_controller
+                .lock(
+                    1,
+                    &mut reader_journal,
+                    Some(Instant::now() + TIMEOUT_UNEXPECTED)
+                )
+                .await,
+            Ok(true)
+        );
+        waiter.rollback();
+        assert_eq!(
+            access_controller.share(2, &mut reader_journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(Some(reader_journal.submit()), NonZeroU32::new(1));
+        assert!(reader.commit().await.is_ok());
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn acquire_all() {
+        const DIR: &str = "access_controller_acquire_all_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+
+        // Access that the journal already had is kept.
+        let transaction = database.transaction();
+        let mut journal = transaction.journal();
+        assert_eq!(
+            access_controller.create(2, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(
+            access_controller
+                .acquire_all(Access::Create, &[1, 2, 3], &mut journal, None)
+                .await,
+            Ok(vec![1, 3])
+        );
+        assert_eq!(journal.lock_count(), 3);
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+
+        // Access gained before the failure is released.
+        let other_transaction = database.transaction();
+        let mut other_journal = other_transaction.journal();
+        assert_eq!(
+            access_controller
+                .acquire_all(Access::Lock, &[4, 5, 1, 6], &mut other_journal, None)
+                .await,
+            Err((2, Error::Conflict))
+        );
+        assert_eq!(other_journal.lock_count(), 0);
+        assert_eq!(access_controller.lock_count(), 3);
+        assert_eq!(
+            access_controller
+                .acquire_all(Access::Lock, &[4, 5], &mut other_journal, None)
+                .await,
+            Ok(vec![4, 5])
+        );
+        assert_eq!(Some(other_journal.submit()), NonZeroU32::new(1));
+
+        // Access gained before the failure is handed over to waiting transactions.
+        let waiting_transaction = database.transaction();
+        let cancellation_token = other_transaction.cancellation_token();
+        let (result, waiting_result) = futures::join!(
+            async {
+                let mut journal = other_transaction.journal();
+                access_controller
+                    .acquire_all(
+                        Access::Lock,
+                        &[7, 1],
+                        &mut journal,
+                        Some(Instant::now() + TIMEOUT_UNEXPECTED),
+                    )
+                    .await
+            },
+            async {
+                wait_for_requests(access_controller, 1, 1).await;
+                let mut waiting_journal = waiting_transaction.journal();
+                let (result, ()) = futures::join!(
+                    access_controller.lock(
+                        7,
+                        &mut waiting_journal,
+                        Some(Instant::now() + TIMEOUT_UNEXPECTED),
+                    ),
+                    async {
+                        wait_for_requests(access_controller, 7, 1).await;
+                        cancellation_token.cancel();
+                    }
+                );
+                assert_eq!(Some(waiting_journal.submit()), NonZeroU32::new(1));
+                result
+            }
+        );
+        assert_eq!(result, Err((1, Error::Cancelled)));
+        assert_eq!(waiting_result, Ok(true));
+        assert!(waiting_transaction.commit().await.is_ok());
+        assert!(transaction.commit().await.is_ok());
+        assert!(other_transaction.commit().await.is_ok());
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn lock_all() {
+        const DIR: &str = "access_controller_lock_all_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+
+        let creator = database.transaction();
+        let mut creator_journal = creator.journal();
+        assert_eq!(
+            access_controller
+                .create(0, &mut creator_journal, None)
+                .await,
+            Ok(true)
+        );
+        assert_eq!(Some(creator_journal.submit()), NonZeroU32::new(1));
+        assert!(creator.commit().await.is_ok());
+
+        let transaction = database.transaction();
+        let mut journal = transaction.journal();
+        assert_eq!(
+            access_controller.lock(6, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(
+            access_controller
+                .lock_all(&[7, 5, 6, 7], &mut journal)
+                .await,
+            Ok(2)
+        );
+        assert_eq!(journal.lock_count(), 3);
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+        assert_eq!(access_controller.scan().count(), 3);
+
+        // The database objects locked before the failure are released.
+        let other_transaction = database.transaction();
+        let mut other_journal = other_transaction.journal();
+        assert_eq!(
+            access_controller
+                .lock_all(&[9, 6, 4, 0], &mut other_journal)
+                .await,
+            Err(Error::Conflict)
+        );
+        assert_eq!(other_journal.lock_count(), 0);
+        assert_eq!(access_controller.scan().count(), 3);
+        let snapshot = database.snapshot();
+        assert_eq!(access_controller.read(0, &snapshot, None).await, Ok(true));
+        drop(snapshot);
+        assert_eq!(
+            access_controller
+                .lock_all_with_retry(
+                    &[6, 9],
+                    &mut other_journal,
+                    Instant::now() + TIMEOUT_UNEXPECTED,
+                    Some(0)
+                )
+                .await,
+            Err(Error::Conflict)
+        );
+
+        // Waits for the conflicting transaction to release the database object.
+        let (result, commit_result) = futures::join!(
+            access_controller.lock_all_with_retry(
+                &[9, 6, 4, 0],
+                &mut other_journal,
+                Instant::now() + TIMEOUT_UNEXPECTED,
+                Some(1)
+            ),
+            async {
+                wait_for_requests(access_controller, 6, 1).await;
+                transaction.commit().await
+            }
+        );
+        assert_eq!(result, Ok(4));
+        assert!(commit_result.is_ok());
+        assert_eq!(Some(other_journal.submit()), NonZeroU32::new(1));
+        assert!(other_transaction.commit().await.is_ok());
+        assert!(remove_dir_all(path).await.is_ok());
     }
 
     #[tokio::test]
-    async fn access_promote_wait() {
-        for num_shared_locks in 1..3 {
-            for block in [AccessAction::Lock, AccessAction::Delete] {
-                for promotion in [AccessAction::Lock, AccessAction::Delete] {
-                    const DIR: &str = "access_controller_promote_wait_test";
-                    let path = Path::new(DIR);
-                    let database = Database::with_path(path).await.unwrap();
-                    let access_controller = database.access_controller();
-                    let transaction = database.transaction();
-                    for i in 0..num_shared_locks {
-                        let mut journal = transaction.journal();
-                        assert_eq!(
-                            take_access_action(
-                                AccessAction::Share,
-                                access_controller,
-                                &mut journal,
-                                None
-                            )
-                            .await,
-                            Ok(i == 0)
-                        );
-                        assert_eq!(Some(journal.submit()), NonZeroU32::new(i + 1));
-                    }
-                    let blocker_transaction = database.transaction();
-                    let mut blocker_journal = blocker_transaction.journal();
-                    let mut journal = transaction.journal();
-                    let (blocker_result, result) = futures::join!(
-                        take_access_action(
-                            block,
-                            access_controller,
-                            &mut blocker_journal,
-                            Some(Instant::now() + TIMEOUT_EXPECTED)
-                        ),
-                        take_access_action(
-                            promotion,
-                            access_controller,
-                            &mut journal,
-                            Some(Instant::now() + TIMEOUT_UNEXPECTED)
-                        )
-                    );
-                    assert_eq!(result, Ok(true));
-                    assert_eq!(blocker_result, Err(Error::Timeout));
-                    assert!(remove_dir_all(path).await.is_ok());
-                }
+    async fn lock_range() {
+        const DIR: &str = "access_controller_lock_range_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+
+        let scanner = database.transaction();
+        let mut scanner_journal = scanner.journal();
+        for i in 0..16 {
+            assert!(access_controller
+                .lock_range(i * 4..i * 4 + 2, &mut scanner_journal)
+                .is_ok());
+        }
+        assert!(access_controller
+            .lock_range(40..48, &mut scanner_journal)
+            .is_ok());
+        assert_eq!(
+            access_controller
+                .create(1, &mut scanner_journal, None)
+                .await,
+            Ok(true)
+        );
+        assert_eq!(Some(scanner_journal.submit()), NonZeroU32::new(1));
+
+        let reader = database.transaction();
+        let mut reader_journal = reader.journal();
+        assert!(access_controller
+            .lock_range(44..52, &mut reader_journal)
+            .is_ok());
+        assert_eq!(Some(reader_journal.submit()), NonZeroU32::new(1));
+
+        let writer = database.transaction();
+        let mut writer_journal = writer.journal();
+        for (object_id, locked) in [(0, true), (2, false), (5, true), (46, true), (50, true)] {
+            let result = access_controller
+                .create(
+                    object_id,
+                    &mut writer_journal,
+                    Some(Instant::now() + TIMEOUT_UNEXPECTED),
+                )
+                .await;
+            if locked {
+                assert_eq!(result, Err(Error::Conflict), "{object_id}");
+            } else {
+                assert_eq!(result, Ok(true), "{object_id}");
             }
         }
+
+        assert!(scanner.commit().await.is_ok());
+        assert_eq!(
+            access_controller.create(5, &mut writer_journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(
+            access_controller
+                .create(46, &mut writer_journal, None)
+                .await,
+            Err(Error::Conflict)
+        );
+
+        reader.rollback();
+        assert_eq!(
+            access_controller
+                .create(46, &mut writer_journal, None)
+                .await,
+            Ok(true)
+        );
+        assert_eq!(Some(writer_journal.submit()), NonZeroU32::new(1));
+        assert!(writer.commit().await.is_ok());
+        assert!(remove_dir_all(path).await.is_ok());
     }
 
     #[tokio::test]
-    async fn access_promote_commit() {
-        for num_shared_locks in 1..3 {
-            for waiting_action in [AccessAction::Lock, AccessAction::Delete] {
-                for promotion in [AccessAction::Lock, AccessAction::Delete] {
-                    const DIR: &str = "access_controller_promote_commit_test";
-                    let path = Path::new(DIR);
-                    let database = Database::with_path(path).await.unwrap();
-                    let access_controller = database.access_controller();
-                    let transaction = database.transaction();
-                    for i in 0..num_shared_locks {
-                        let mut journal = transaction.journal();
-                        assert_eq!(
-                            take_access_action(
-                                AccessAction::Share,
-                                access_controller,
-                                &mut journal,
-                                None
-                            )
-                            .await,
-                            Ok(i == 0)
-                        );
-                        assert_eq!(Some(journal.submit()), NonZeroU32::new(i + 1));
-                    }
-                    let waiting_transaction = database.transaction();
-                    let mut waiting_journal = waiting_transaction.journal();
-                    let (waiting_result, _) = futures::join!(
-                        take_access_action(
-                            waiting_action,
-                            access_controller,
-                            &mut waiting_journal,
-                            Some(Instant::now() + TIMEOUT_UNEXPECTED)
-                        ),
-                        async {
-                            let mut journal = transaction.journal();
-                            assert_eq!(
-                                take_access_action(
-                                    promotion,
-                                    access_controller,
-                                    &mut journal,
-                                    Some(Instant::now() + TIMEOUT_UNEXPECTED),
-                                )
-                                .await,
-                                Ok(true)
-                            );
-                            assert_eq!(
-                                Some(journal.submit()),
-                                NonZeroU32::new(num_shared_locks + 1)
-                            );
-                            assert!(transaction.commit().await.is_ok());
-                        }
-                    );
-                    if promotion == AccessAction::Delete {
-                        assert_eq!(waiting_result, Err(Error::SerializationFailure));
-                    } else {
-                        assert_eq!(waiting_result, Ok(true));
-                    }
-                    assert!(remove_dir_all(path).await.is_ok());
-                }
+    async fn bulk_create() {
+        const DIR: &str = "access_controller_bulk_create_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        let transaction = database.transaction();
+        let mut journal = transaction.journal();
+        assert_eq!(
+            access_controller.create(2, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(
+            access_controller
+                .bulk_create(&[3, 1, 2, 3], &mut journal, None)
+                .await,
+            Ok(2)
+        );
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+
+        // The database objects created before the failure are released.
+        let other_transaction = database.transaction();
+        let mut other_journal = other_transaction.journal();
+        assert_eq!(
+            access_controller
+                .bulk_create(&[4, 3, 0], &mut other_journal, None)
+                .await,
+            Err(Error::Conflict)
+        );
+        assert_eq!(access_controller.lock_count(), 3);
+        assert_eq!(
+            access_controller
+                .bulk_create(&[6, 4, 5], &mut other_journal, None)
+                .await,
+            Ok(3)
+        );
+        assert_eq!(Some(other_journal.submit()), NonZeroU32::new(1));
+
+        // Transactions creating overlapping sets of database objects in different orders do not
+        // deadlock each other.
+        let (result, result_other) = futures::join!(
+            async {
+                let transaction = database.transaction();
+                let mut journal = transaction.journal();
+                let result = access_controller
+                    .bulk_create(
+                        &[9, 8, 7],
+                        &mut journal,
+                        Some(Instant::now() + TIMEOUT_UNEXPECTED),
+                    )
+                    .await;
+                assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+                assert!(transaction.commit().await.is_ok());
+                result
+            },
+            async {
+                let transaction = database.transaction();
+                let mut journal = transaction.journal();
+                let result = access_controller
+                    .bulk_create(
+                        &[7, 8, 9],
+                        &mut journal,
+                        Some(Instant::now() + TIMEOUT_UNEXPECTED),
+                    )
+                    .await;
+                assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+                assert!(transaction.commit().await.is_ok());
+                result
             }
-        }
+        );
+        assert!(
+            result.is_ok() != result_other.is_ok(),
+            "{result:?} {result_other:?}"
+        );
+        assert_eq!(result.or(result_other), Ok(3));
+
+        assert!(transaction.commit().await.is_ok());
+        assert!(other_transaction.commit().await.is_ok());
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn scan() {
+        const DIR: &str = "access_controller_scan_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        let transaction = database.transaction();
+        let mut journal = transaction.journal();
+        assert_eq!(
+            access_controller.create(0, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(
+            access_controller.share(1, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+        let other_transaction = database.transaction();
+        let mut other_journal = other_transaction.journal();
+        assert_eq!(
+            access_controller.share(1, &mut other_journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(
+            access_controller.lock(2, &mut other_journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(Some(other_journal.submit()), NonZeroU32::new(1));
+
+        let mut lock_info_list: Vec<LockInfo> = access_controller.scan().collect();
+        lock_info_list.sort_by_key(LockInfo::object_id);
+        assert_eq!(lock_info_list.len(), 3);
+        assert_eq!(access_controller.lock_count(), 3);
+        assert_eq!(lock_info_list[0].mode(), LockMode::Exclusive);
+        assert_eq!(lock_info_list[0].owners(), &[transaction.id()]);
+        assert_eq!(lock_info_list[1].mode(), LockMode::Shared);
+        let mut owners = vec![transaction.id(), other_transaction.id()];
+        owners.sort_unstable();
+        assert_eq!(lock_info_list[1].owners(), owners.as_slice());
+        assert_eq!(lock_info_list[2].mode(), LockMode::Exclusive);
+        assert_eq!(lock_info_list[2].owners(), &[other_transaction.id()]);
+
+        // Committed or rolled back transactions do not own database objects.
+        let other_transaction_id = other_transaction.id();
+        other_transaction.rollback();
+        let lock_info_list: Vec<LockInfo> = access_controller.scan().collect();
+        assert_eq!(lock_info_list.len(), 2);
+        assert!(lock_info_list
+            .iter()
+            .all(|l| !l.owners().contains(&other_transaction_id)));
+        assert!(transaction.commit().await.is_ok());
+        assert_eq!(access_controller.scan().count(), 0);
+        assert!(remove_dir_all(path).await.is_ok());
     }
 
     #[tokio::test]
@@ -2593,17 +5380,23 @@ mod tests {
                             futures::join!(transaction_action_runner, post_action_runner).1
                         };
 
-                        if ((access != AccessAction::Create
+                        if (access != AccessAction::Create
                             || transaction_action == TransactionAction::Commit)
-                            && post_access == AccessAction::Create)
-                            || (access == AccessAction::Delete
-                                && transaction_action == TransactionAction::Commit)
+                            && post_access == AccessAction::Create
                         {
                             assert_eq!(
                                 result,
                                 Err(Error::SerializationFailure),
                                 "{access:?} {transaction_action:?} {post_access:?}"
                             );
+                        } else if access == AccessAction::Delete
+                            && transaction_action == TransactionAction::Commit
+                        {
+                            assert_eq!(
+                                result,
+                                Err(Error::Conflict),
+                                "{access:?} {transaction_action:?} {post_access:?}"
+                            );
                         } else {
                             assert_eq!(
                                 result,
@@ -2690,8 +5483,8 @@ mod tests {
                             assert_eq!(result_post, Ok(true));
                         }
                         (AccessAction::Delete, _, _) => {
-                            assert_eq!(result, Err(Error::SerializationFailure));
-                            assert_eq!(result_post, Err(Error::SerializationFailure));
+                            assert_eq!(result, Err(Error::Conflict));
+                            assert_eq!(result_post, Err(Error::Conflict));
                         }
                     };
 
@@ -2914,6 +5707,62 @@ mod tests {
         assert!(remove_dir_all(path).await.is_ok());
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 16)]
+    async fn parallel_deadlock_ring() {
+        const DIR: &str = "access_controller_parallel_deadlock_ring_test";
+        let path = Path::new(DIR);
+        let database = Arc::new(Database::with_path(path).await.unwrap());
+        let num_tasks = 8;
+        let num_objects: u64 = 8;
+        let barrier = Arc::new(Barrier::new(num_tasks));
+        let num_deadlocks = Arc::new(AtomicUsize::default());
+        let mut task_handles = Vec::with_capacity(num_tasks);
+        for object_id in 0..num_objects {
+            let barrier_clone = barrier.clone();
+            let database_clone = database.clone();
+            let num_deadlocks_clone = num_deadlocks.clone();
+            task_handles.push(tokio::spawn(async move {
+                let access_controller = database_clone.access_controller();
+                let transaction = database_clone.transaction();
+                let mut journal = transaction.journal();
+                assert_eq!(
+                    access_controller.lock(object_id, &mut journal, None).await,
+                    Ok(true)
+                );
+                assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+                barrier_clone.wait().await;
+
+                // Each transaction waits for the next one, forming a ring.
+                let mut journal = transaction.journal();
+                match access_controller
+                    .lock(
+                        (object_id + 1) % num_objects,
+                        &mut journal,
+                        Some(Instant::now() + TIMEOUT_UNEXPECTED),
+                    )
+                    .await
+                {
+                    Ok(result) => {
+                        assert!(result);
+                        assert_eq!(Some(journal.submit()), NonZeroU32::new(2));
+                        assert!(transaction.commit().await.is_ok());
+                    }
+                    Err(error) => {
+                        assert_eq!(error, Error::Deadlock);
+                        num_deadlocks_clone.fetch_add(1, Relaxed);
+                        drop(journal);
+                        transaction.rollback();
+                    }
+                }
+            }));
+        }
+        for r in futures::future::join_all(task_handles).await {
+            assert!(r.is_ok());
+        }
+        assert_eq!(num_deadlocks.load(Relaxed), 1);
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 16)]
     async fn parallel_create_delete() {
         const DIR: &str = "access_controller_parallel_create_delete_test";
@@ -2984,7 +5833,7 @@ mod tests {
                         }
                     }
                     Err(error) => {
-                        assert_eq!(error, Error::SerializationFailure);
+                        assert_eq!(error, Error::Conflict);
                         assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
                         transaction.rollback();
                     }
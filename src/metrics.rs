@@ -0,0 +1,109 @@
+// SPDX-FileCopyrightText: 2023 Changgyoo Park <wvwwvwwv@me.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Statistics emitted through the `metrics` crate.
+//!
+//! Every function in this module compiles to nothing unless the `metrics` feature is enabled. The
+//! functions taking a [`Collector`] also feed the lock acquisition statistics that are collected
+//! if the `statistics` feature is enabled.
+
+use super::statistics::Collector;
+use super::Error;
+use std::future::Future;
+#[cfg(any(feature = "metrics", feature = "statistics"))]
+use std::time::Instant;
+
+/// [`Stopwatch`] measures the latency of an operation.
+///
+/// It is a zero-sized type if the `metrics` feature is disabled.
+#[derive(Debug)]
+pub(super) struct Stopwatch {
+    /// The instant when the operation started.
+    #[cfg(feature = "metrics")]
+    started: Instant,
+}
+
+impl Stopwatch {
+    /// Starts measuring the latency of an operation.
+    #[inline]
+    pub(super) fn start() -> Stopwatch {
+        Stopwatch {
+            #[cfg(feature = "metrics")]
+            started: Instant::now(),
+        }
+    }
+
+    /// Records the latency of a transaction commit.
+    #[inline]
+    pub(super) fn record_commit(self) {
+        #[cfg(feature = "metrics")]
+        ::metrics::histogram!("tlt_commit_latency_seconds")
+            .record(self.started.elapsed().as_secs_f64());
+    }
+}
+
+/// Records the result of an attempt to gain access to a database object.
+#[inline]
+pub(super) fn record_access<T>(statistics: &Collector, object_id: u64, result: &Result<T, Error>) {
+    #[cfg(feature = "metrics")]
+    match result {
+        Ok(_) => ::metrics::counter!("tlt_access_granted").increment(1),
+        Err(Error::Conflict) => ::metrics::counter!("tlt_access_conflicts").increment(1),
+        Err(Error::Deadlock) => ::metrics::counter!("tlt_access_deadlocks").increment(1),
+        Err(Error::Timeout) => ::metrics::counter!("tlt_access_timeouts").increment(1),
+        Err(_) => ::metrics::counter!("tlt_access_failures").increment(1),
+    }
+    statistics.record_access(object_id, result);
+}
+
+/// Waits for the response to a request on the database object, and records the wait time.
+#[inline]
+pub(super) async fn wait<F: Future<Output = Result<bool, Error>>>(
+    statistics: &Collector,
+    object_id: u64,
+    response: F,
+) -> Result<bool, Error> {
+    #[cfg(any(feature = "metrics", feature = "statistics"))]
+    {
+        let wait_started = Instant::now();
+        let result = response.await;
+        let elapsed = wait_started.elapsed();
+        #[cfg(feature = "metrics")]
+        ::metrics::histogram!("tlt_wait_time_seconds").record(elapsed.as_secs_f64());
+        statistics.record_wait(object_id, elapsed, &result);
+        result
+    }
+    #[cfg(not(any(feature = "metrics", feature = "statistics")))]
+    {
+        let _ = (statistics, object_id);
+        response.await
+    }
+}
+
+/// Records the number of requests in a wait queue after a new request was pushed into it.
+#[inline]
+pub(super) fn record_wait_queue_depth(depth: usize) {
+    #[cfg(feature = "metrics")]
+    {
+        ::metrics::counter!("tlt_access_waits").increment(1);
+        ::metrics::histogram!("tlt_wait_queue_depth")
+            .record(f64::from(u32::try_from(depth).unwrap_or(u32::MAX)));
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = depth;
+}
+
+/// Records that access control data was added to the access controller.
+#[inline]
+pub(super) fn record_entry_inserted() {
+    #[cfg(feature = "metrics")]
+    ::metrics::gauge!("tlt_access_control_entries").increment(1.0);
+}
+
+/// Records that access control data was removed from the access controller.
+#[inline]
+pub(super) fn record_entry_removed() {
+    #[cfg(feature = "metrics")]
+    ::metrics::gauge!("tlt_access_control_entries").decrement(1.0);
+}
@@ -0,0 +1,258 @@
+// SPDX-FileCopyrightText: 2023 Changgyoo Park <wvwwvwwv@me.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use super::journal::Anchor as JournalAnchor;
+use super::Sequencer;
+use scc::ebr;
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::{PoisonError, RwLock};
+
+/// [`RangeLockTable`] keeps locks on ranges of database object identifiers.
+///
+/// Shared range locks are compatible with each other, whereas they prevent other transactions from
+/// creating database objects in the ranges. Intention locks denote that the owner has gained access
+/// to database objects in the ranges, and exclusive range locks are incompatible with any range
+/// locks of other transactions, preventing them from gaining access to database objects in the
+/// ranges. A range lock is released when the transaction is ended or the
+/// [`Journal`](super::Journal) that acquired it is rolled back.
+#[derive(Debug)]
+pub(super) struct RangeLockTable<S: Sequencer> {
+    /// Range locks indexed by the start of the ranges.
+    ranges: RwLock<RangeMap<S>>,
+}
+
+/// Range locks indexed by the start of the ranges.
+#[derive(Debug)]
+struct RangeMap<S: Sequencer> {
+    /// Range locks starting at each identifier.
+    locks: BTreeMap<u64, Vec<RangeLock<S>>>,
+
+    /// The length of the longest range in the table.
+    ///
+    /// It is recomputed when released range locks are removed from the table, and ranges containing an identifier can only start within this distance from the identifier.
+    max_len: u64,
+}
+
+/// A lock on a range of database object identifiers.
+#[derive(Debug)]
+struct RangeLock<S: Sequencer> {
+    /// The end of the range.
+    end: u64,
+
+    /// The lock mode.
+    mode: Mode,
+
+    /// The owner of the range lock.
+    owner: ebr::Arc<JournalAnchor<S>>,
+}
+
+/// Range lock modes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Mode {
+    /// The range is protected against creation of database objects by other transactions.
+    Shared,
+
+    /// The owner has gained access to database objects in the range.
+    Intention,
+
+    /// Other transactions are not allowed to gain access to database objects in the range.
+    Exclusive,
+}
+
+impl<S: Sequencer> RangeLockTable<S> {
+    /// Locks the range on behalf of the owner in shared mode.
+    ///
+    /// Returns `false` if another active transaction has exclusively locked an overlapping range.
+    pub(super) fn lock_shared(
+        &self,
+        range: Range<u64>,
+        owner: &ebr::Arc<JournalAnchor<S>>,
+    ) -> bool {
+        self.try_lock(range, Mode::Shared, owner)
+    }
+
+    /// Acquires an intention lock on the range on behalf of the owner.
+    ///
+    /// Returns `false` if another active transaction has exclusively locked an overlapping range.
+    pub(super) fn lock_intention(
+        &self,
+        range: Range<u64>,
+        owner: &ebr::Arc<JournalAnchor<S>>,
+    ) -> bool {
+        self.try_lock(range, Mode::Intention, owner)
+    }
+
+    /// Locks the range on behalf of the owner in exclusive mode.
+    ///
+    /// Returns `false` if another active transaction has locked an overlapping range.
+    pub(super) fn lock_exclusive(
+        &self,
+        range: Range<u64>,
+        owner: &ebr::Arc<JournalAnchor<S>>,
+    ) -> bool {
+        self.try_lock(range, Mode::Exclusive, owner)
+    }
+
+    /// Checks if another active transaction has locked a range containing the identifier in
+    /// shared or exclusive mode.
+    ///
+    /// Range locks that were released are removed from the table.
+    pub(super) fn is_locked_by_others(
+        &self,
+        object_id: u64,
+        new_owner: &ebr::Arc<JournalAnchor<S>>,
+    ) -> bool {
+        self.find(object_id, |l| {
+            l.mode != Mode::Intention && l.is_owned_by_others(new_owner)
+        })
+    }
+
+    /// Checks if another active transaction has exclusively locked a range containing the
+    /// identifier.
+    pub(super) fn is_exclusively_locked_by_others(
+        &self,
+        object_id: u64,
+        new_owner: &ebr::Arc<JournalAnchor<S>>,
+    ) -> bool {
+        self.find(object_id, |l| {
+            l.mode == Mode::Exclusive && l.is_owned_by_others(new_owner)
+        })
+    }
+
+    /// Checks if the transaction has exclusively locked a range containing the identifier.
+    pub(super) fn is_exclusively_locked_by(
+        &self,
+        object_id: u64,
+        owner: &ebr::Arc<JournalAnchor<S>>,
+    ) -> bool {
+        self.find(object_id, |l| {
+            l.mode == Mode::Exclusive && l.owner.transaction_id() == owner.transaction_id()
+        })
+    }
+
+    /// Removes range locks that were released from the table.
+    pub(super) fn prune(&self) {
+        if self
+            .ranges
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .locks
+            .is_empty()
+        {
+            return;
+        }
+        self.ranges
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .prune();
+    }
+
+    /// Locks the range on behalf of the owner unless it conflicts with range locks of other
+    /// transactions.
+    fn try_lock(&self, range: Range<u64>, mode: Mode, owner: &ebr::Arc<JournalAnchor<S>>) -> bool {
+        if range.is_empty() {
+            return true;
+        }
+        let mut ranges = self.ranges.write().unwrap_or_else(PoisonError::into_inner);
+        let lower_bound = range.start.saturating_sub(ranges.max_len);
+        if ranges
+            .locks
+            .range(lower_bound..range.end)
+            .flat_map(|(_, locks)| locks.iter())
+            .any(|l| {
+                l.end > range.start
+                    && (mode == Mode::Exclusive || l.mode == Mode::Exclusive)
+                    && l.is_owned_by_others(owner)
+            })
+        {
+            return false;
+        }
+        ranges.insert(range, mode, owner);
+        true
+    }
+
+    /// Checks if any active range lock containing the identifier satisfies the condition.
+    ///
+    /// Range locks that were released are removed from the table.
+    fn find<F: Fn(&RangeLock<S>) -> bool>(&self, object_id: u64, condition: F) -> bool {
+        let mut released = false;
+        {
+            let ranges = self.ranges.read().unwrap_or_else(PoisonError::into_inner);
+            if ranges.locks.is_empty() {
+                return false;
+            }
+            let lower_bound = object_id.saturating_sub(ranges.max_len);
+            for lock in ranges
+                .locks
+                .range(lower_bound..=object_id)
+                .flat_map(|(_, locks)| locks.iter())
+            {
+                if lock.owner.is_terminated() {
+                    released = true;
+                } else if object_id < lock.end && condition(lock) {
+                    return true;
+                }
+            }
+        }
+        if released {
+            self.prune();
+        }
+        false
+    }
+}
+
+impl<S: Sequencer> RangeMap<S> {
+    /// Removes range locks that were released, and recomputes the maximum range length.
+    fn prune(&mut self) {
+        self.locks.retain(|_, locks| {
+            locks.retain(|l| !l.owner.is_terminated());
+            !locks.is_empty()
+        });
+        self.max_len = self
+            .locks
+            .iter()
+            .flat_map(|(start, locks)| locks.iter().map(move |l| l.end - start))
+            .max()
+            .unwrap_or(0);
+    }
+
+    /// Inserts a range lock unless the owner already holds the same range lock.
+    fn insert(&mut self, range: Range<u64>, mode: Mode, owner: &ebr::Arc<JournalAnchor<S>>) {
+        self.max_len = self.max_len.max(range.end - range.start);
+        let locks = self.locks.entry(range.start).or_default();
+        locks.retain(|l| !l.owner.is_terminated());
+        if !locks
+            .iter()
+            .any(|l| l.end == range.end && l.mode == mode && l.owner.as_ptr() == owner.as_ptr())
+        {
+            // The table needs to be pruned when the owner is ended.
+            owner.set_wake_up_others();
+            locks.push(RangeLock {
+                end: range.end,
+                mode,
+                owner: owner.clone(),
+            });
+        }
+    }
+}
+
+impl<S: Sequencer> RangeLock<S> {
+    /// Checks if the range lock is owned by another active transaction.
+    fn is_owned_by_others(&self, owner: &ebr::Arc<JournalAnchor<S>>) -> bool {
+        !self.owner.is_terminated() && self.owner.transaction_id() != owner.transaction_id()
+    }
+}
+
+impl<S: Sequencer> Default for RangeLockTable<S> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            ranges: RwLock::new(RangeMap {
+                locks: BTreeMap::new(),
+                max_len: 0,
+            }),
+        }
+    }
+}
@@ -0,0 +1,376 @@
+// SPDX-FileCopyrightText: 2023 Changgyoo Park <wvwwvwwv@me.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Lock acquisition statistics.
+//!
+//! Statistics are only collected if the `statistics` feature is enabled, otherwise [`Collector`]
+//! is a zero-sized type and every method of it compiles to nothing.
+
+use super::Error;
+#[cfg(feature = "statistics")]
+use std::sync::atomic::Ordering::Relaxed;
+#[cfg(feature = "statistics")]
+use std::sync::atomic::{AtomicU32, AtomicU64};
+#[cfg(any(feature = "metrics", feature = "statistics"))]
+use std::time::Duration;
+
+/// The upper bounds of the wait time histogram buckets.
+///
+/// The last bucket of the histogram counts waits that took longer than the last bound.
+#[cfg(feature = "statistics")]
+pub const WAIT_TIME_BUCKETS: [Duration; 6] = [
+    Duration::from_micros(10),
+    Duration::from_micros(100),
+    Duration::from_millis(1),
+    Duration::from_millis(10),
+    Duration::from_millis(100),
+    Duration::from_secs(1),
+];
+
+/// The number of rows in the count-min sketch.
+#[cfg(feature = "statistics")]
+const SKETCH_DEPTH: usize = 4;
+
+/// The number of counters in each row of the count-min sketch.
+#[cfg(feature = "statistics")]
+const SKETCH_WIDTH: u64 = 256;
+
+/// The number of slots for contended database objects.
+#[cfg(feature = "statistics")]
+const MAX_CANDIDATES: u64 = 64;
+
+/// The value of an empty candidate slot.
+///
+/// The database object identified as `u64::MAX` is never reported as contended.
+#[cfg(feature = "statistics")]
+const EMPTY_CANDIDATE: u64 = u64::MAX;
+
+/// Odd multipliers to derive a different hash value for each row of the count-min sketch.
+#[cfg(feature = "statistics")]
+const SKETCH_SEEDS: [u64; SKETCH_DEPTH] = [
+    0x9E37_79B9_7F4A_7C15,
+    0xC2B2_AE3D_27D4_EB4F,
+    0x1656_67B1_9E37_79F9,
+    0x27D4_EB2F_1656_67C5,
+];
+
+/// [`Statistics`] is a snapshot of the lock acquisition statistics of an
+/// [`AccessController`](super::AccessController).
+///
+/// It is only available if the `statistics` feature is enabled.
+#[cfg(feature = "statistics")]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Statistics {
+    /// The number of successful attempts to gain access to a database object.
+    acquisitions: u64,
+
+    /// The number of successful attempts that had to wait for other transactions.
+    granted_after_wait: u64,
+
+    /// The number of attempts that had to wait for other transactions.
+    waits: u64,
+
+    /// The wait time histogram.
+    wait_time_histogram: [u64; WAIT_TIME_BUCKETS.len() + 1],
+
+    /// The number of conflicts returned.
+    conflicts: u64,
+
+    /// The number of deadlocks detected.
+    deadlocks: u64,
+}
+
+/// [`Collector`] maintains lock acquisition statistics with relaxed atomics.
+///
+/// It is a zero-sized type if the `statistics` feature is disabled.
+#[derive(Debug, Default)]
+pub(super) struct Collector {
+    /// See [`Statistics::acquisitions`].
+    #[cfg(feature = "statistics")]
+    acquisitions: AtomicU64,
+
+    /// The number of successful attempts that had to wait for other transactions.
+    #[cfg(feature = "statistics")]
+    granted_after_wait: AtomicU64,
+
+    /// See [`Statistics::waits`].
+    #[cfg(feature = "statistics")]
+    waits: AtomicU64,
+
+    /// See [`Statistics::wait_time_histogram`].
+    #[cfg(feature = "statistics")]
+    wait_time_histogram: [AtomicU64; WAIT_TIME_BUCKETS.len() + 1],
+
+    /// See [`Statistics::conflicts`].
+    #[cfg(feature = "statistics")]
+    conflicts: AtomicU64,
+
+    /// See [`Statistics::deadlocks`].
+    #[cfg(feature = "statistics")]
+    deadlocks: AtomicU64,
+
+    /// Database objects that were contended.
+    #[cfg(feature = "statistics")]
+    contention: ContentionSketch,
+}
+
+/// [`ContentionSketch`] approximately counts how often each database object was contended.
+///
+/// A count-min sketch estimates the counts, and a fixed number of slots remembers the database
+/// objects with high estimated counts. Each database object can only be stored in two slots
+/// derived from its identifier, and it replaces the resident of either slot if the resident has a
+/// lower estimated count, therefore no locks are acquired when recording a contended database
+/// object.
+#[cfg(feature = "statistics")]
+#[derive(Debug)]
+struct ContentionSketch {
+    /// `SKETCH_DEPTH` rows of `SKETCH_WIDTH` counters.
+    counters: Vec<AtomicU32>,
+
+    /// Slots for database objects with high estimated counts.
+    candidates: Vec<AtomicU64>,
+}
+
+#[cfg(feature = "statistics")]
+impl Statistics {
+    /// Returns the number of successful attempts to gain access to a database object.
+    #[inline]
+    #[must_use]
+    pub fn acquisitions(&self) -> u64 {
+        self.acquisitions
+    }
+
+    /// Returns the number of successful attempts that did not have to wait.
+    #[inline]
+    #[must_use]
+    pub fn immediate_grants(&self) -> u64 {
+        self.acquisitions.saturating_sub(self.granted_after_wait)
+    }
+
+    /// Returns the number of attempts that had to wait for other transactions.
+    #[inline]
+    #[must_use]
+    pub fn waits(&self) -> u64 {
+        self.waits
+    }
+
+    /// Returns the wait time histogram.
+    ///
+    /// Each element corresponds to an upper bound in [`WAIT_TIME_BUCKETS`], and the last element
+    /// counts waits that took longer than the last bound.
+    #[inline]
+    #[must_use]
+    pub fn wait_time_histogram(&self) -> &[u64] {
+        &self.wait_time_histogram
+    }
+
+    /// Returns the number of [`Error::Conflict`] returned.
+    #[inline]
+    #[must_use]
+    pub fn conflicts(&self) -> u64 {
+        self.conflicts
+    }
+
+    /// Returns the number of [`Error::Deadlock`] returned.
+    #[inline]
+    #[must_use]
+    pub fn deadlocks(&self) -> u64 {
+        self.deadlocks
+    }
+}
+
+impl Collector {
+    /// Records the result of an attempt to gain access to a database object.
+    #[inline]
+    pub(super) fn record_access<T>(&self, object_id: u64, result: &Result<T, Error>) {
+        #[cfg(feature = "statistics")]
+        match result {
+            Ok(_) => {
+                self.acquisitions.fetch_add(1, Relaxed);
+            }
+            Err(Error::Conflict) => {
+                self.conflicts.fetch_add(1, Relaxed);
+                self.contention.record(object_id);
+            }
+            Err(Error::Deadlock) => {
+                self.deadlocks.fetch_add(1, Relaxed);
+                self.contention.record(object_id);
+            }
+            Err(_) => (),
+        }
+        #[cfg(not(feature = "statistics"))]
+        let _ = (object_id, result);
+    }
+
+    /// Records a wait for a database object that took `elapsed` and ended with `result`.
+    #[cfg(any(feature = "metrics", feature = "statistics"))]
+    #[inline]
+    pub(super) fn record_wait(
+        &self,
+        object_id: u64,
+        elapsed: Duration,
+        result: &Result<bool, Error>,
+    ) {
+        #[cfg(feature = "statistics")]
+        {
+            self.waits.fetch_add(1, Relaxed);
+            self.contention.record(object_id);
+            let bucket = WAIT_TIME_BUCKETS
+                .iter()
+                .position(|b| elapsed <= *b)
+                .unwrap_or(WAIT_TIME_BUCKETS.len());
+            self.wait_time_histogram[bucket].fetch_add(1, Relaxed);
+            if result.is_ok() {
+                self.granted_after_wait.fetch_add(1, Relaxed);
+            }
+        }
+        #[cfg(not(feature = "statistics"))]
+        let _ = (object_id, elapsed, result);
+    }
+
+    /// Takes a snapshot of the statistics.
+    #[cfg(feature = "statistics")]
+    pub(super) fn snapshot(&self) -> Statistics {
+        let mut wait_time_histogram = [0; WAIT_TIME_BUCKETS.len() + 1];
+        for (count, counter) in wait_time_histogram
+            .iter_mut()
+            .zip(self.wait_time_histogram.iter())
+        {
+            *count = counter.load(Relaxed);
+        }
+        Statistics {
+            acquisitions: self.acquisitions.load(Relaxed),
+            granted_after_wait: self.granted_after_wait.load(Relaxed),
+            waits: self.waits.load(Relaxed),
+            wait_time_histogram,
+            conflicts: self.conflicts.load(Relaxed),
+            deadlocks: self.deadlocks.load(Relaxed),
+        }
+    }
+
+    /// Returns the most frequently contended database objects with their estimated counts.
+    #[cfg(feature = "statistics")]
+    pub(super) fn top_contended(&self, n: usize) -> Vec<(u64, u32)> {
+        self.contention.top(n)
+    }
+
+    /// Resets all the counters.
+    #[cfg(feature = "statistics")]
+    pub(super) fn reset(&self) {
+        self.acquisitions.store(0, Relaxed);
+        self.granted_after_wait.store(0, Relaxed);
+        self.waits.store(0, Relaxed);
+        self.wait_time_histogram
+            .iter()
+            .for_each(|c| c.store(0, Relaxed));
+        self.conflicts.store(0, Relaxed);
+        self.deadlocks.store(0, Relaxed);
+        self.contention.reset();
+    }
+}
+
+#[cfg(feature = "statistics")]
+impl ContentionSketch {
+    /// Returns the index of the counter for the database object in the row.
+    fn index(row: usize, object_id: u64) -> usize {
+        let hash = object_id.wrapping_mul(SKETCH_SEEDS[row]) >> 32;
+        row * usize::try_from(SKETCH_WIDTH).unwrap_or_default()
+            + usize::try_from(hash % SKETCH_WIDTH).unwrap_or_default()
+    }
+
+    /// Returns the indexes of the two slots that can store the database object.
+    fn slots(object_id: u64) -> [usize; 2] {
+        [0, 1].map(|row| {
+            let hash = object_id.wrapping_mul(SKETCH_SEEDS[row]) >> 32;
+            usize::try_from(hash % MAX_CANDIDATES).unwrap_or_default()
+        })
+    }
+
+    /// Increments the count of the database object, and returns the new estimated count.
+    fn increment(&self, object_id: u64) -> u32 {
+        (0..SKETCH_DEPTH)
+            .map(|row| {
+                self.counters[Self::index(row, object_id)]
+                    .fetch_add(1, Relaxed)
+                    .saturating_add(1)
+            })
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// Returns the estimated count of the database object.
+    fn estimate(&self, object_id: u64) -> u32 {
+        (0..SKETCH_DEPTH)
+            .map(|row| self.counters[Self::index(row, object_id)].load(Relaxed))
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// Records that the database object was contended.
+    fn record(&self, object_id: u64) {
+        let estimate = self.increment(object_id);
+        if object_id == EMPTY_CANDIDATE {
+            return;
+        }
+        let slots = Self::slots(object_id);
+        if slots
+            .iter()
+            .any(|s| self.candidates[*s].load(Relaxed) == object_id)
+        {
+            return;
+        }
+        for slot in slots {
+            let resident = self.candidates[slot].load(Relaxed);
+            if (resident == EMPTY_CANDIDATE || self.estimate(resident) < estimate)
+                && self.candidates[slot]
+                    .compare_exchange(resident, object_id, Relaxed, Relaxed)
+                    .is_ok()
+            {
+                return;
+            }
+        }
+    }
+
+    /// Returns at most `n` database objects with the highest estimated counts.
+    fn top(&self, n: usize) -> Vec<(u64, u32)> {
+        let mut object_ids: Vec<u64> = self
+            .candidates
+            .iter()
+            .map(|c| c.load(Relaxed))
+            .filter(|id| *id != EMPTY_CANDIDATE)
+            .collect();
+        object_ids.sort_unstable();
+        object_ids.dedup();
+        let mut candidates: Vec<(u64, u32)> = object_ids
+            .into_iter()
+            .map(|id| (id, self.estimate(id)))
+            .filter(|(_, count)| *count != 0)
+            .collect();
+        candidates.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        candidates.truncate(n);
+        candidates
+    }
+
+    /// Clears the sketch.
+    fn reset(&self) {
+        self.counters.iter().for_each(|c| c.store(0, Relaxed));
+        self.candidates
+            .iter()
+            .for_each(|c| c.store(EMPTY_CANDIDATE, Relaxed));
+    }
+}
+
+#[cfg(feature = "statistics")]
+impl Default for ContentionSketch {
+    #[inline]
+    fn default() -> Self {
+        let len = SKETCH_DEPTH * usize::try_from(SKETCH_WIDTH).unwrap_or_default();
+        Self {
+            counters: (0..len).map(|_| AtomicU32::new(0)).collect(),
+            candidates: (0..MAX_CANDIDATES)
+                .map(|_| AtomicU64::new(EMPTY_CANDIDATE))
+                .collect(),
+        }
+    }
+}
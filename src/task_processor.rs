@@ -136,7 +136,13 @@ impl TaskProcessor {
             // send buffer is full.
             Self::process_time_critical_tasks(thread_local_data);
 
-            // Perform MVCC garbage collection.
+            // Perform MVCC garbage collection; the garbage collection horizon is advanced to the
+            // oldest live snapshot before any database object versions older than it are pruned.
+            let oldest = thread_local_data.kernel.sequencer().min(Acquire);
+            thread_local_data
+                .kernel
+                .access_controller()
+                .advance_gc_horizon(oldest);
             let mut operation_count = 0;
             let mut monitored_containers = take(&mut thread_local_data.monitored_containers);
             monitored_containers.retain(|name| {
@@ -144,7 +150,6 @@ impl TaskProcessor {
                     .kernel
                     .container(name.as_str(), &ebr::Barrier::new())
                 {
-                    let oldest = thread_local_data.kernel.sequencer().min(Acquire);
                     let versioned_record_iter = container.iter_versioned_records();
                     let mut num_versioned_records = 0;
                     for object_id in versioned_record_iter {
@@ -224,7 +229,7 @@ impl TaskProcessor {
     /// Performs time critical tasks.
     ///
     /// It firstly scans the access controller and cleans up access control information associated
-    /// with monitored database objects, then wakes up every expired [`Waker`] instances.
+    /// with monitored database objects and released range locks, then wakes up every expired [`Waker`] instances.
     fn process_time_critical_tasks<S: Sequencer, P: PersistenceLayer<S>>(
         thread_local_data: &mut ThreadLocalData<S, P>,
     ) {
@@ -232,6 +237,7 @@ impl TaskProcessor {
         thread_local_data
             .monitored_object_ids
             .retain(|object_id| access_controller.transfer_ownership_sync(*object_id));
+        access_controller.prune_range_locks();
 
         let mut new_wait_duration = DEFAULT_CHECK_INTERAL;
         let now = Instant::now();
@@ -2,19 +2,24 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+use super::cancellation::CancellationToken;
 use super::journal::Anchor as JournalAnchor;
+use super::metrics::Stopwatch;
 use super::{Accessor, Error, LockTable};
 use scc::ebr;
 use scc::Bag;
 use std::collections::hash_map;
 use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::future::Future;
+use std::mem::take;
 use std::num::{NonZeroU32, NonZeroU64};
+use std::panic::{self, AssertUnwindSafe};
 use std::pin::Pin;
 use std::ptr::addr_of;
 use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
 use std::sync::atomic::{AtomicU64, AtomicUsize};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, PoisonError};
 use std::task::Waker;
 use std::task::{Context, Poll};
 
@@ -48,6 +53,12 @@ pub struct Transaction<'l, S: Sequencer, P: PersistenceLayer<S>> {
     /// It is `None` if the transaction is not part of a distributed transaction.
     xid: Option<Box<[u8]>>,
 
+    /// Callbacks to invoke when the [`Transaction`] is ended.
+    hooks: Hooks<S>,
+
+    /// Cancels access requests of the [`Transaction`] that are waiting for other transactions.
+    cancellation_token: CancellationToken,
+
     /// A piece of data that is shared between [`Journal`] and [`Transaction`].
     ///
     /// It outlives the [`Transaction`], and it is dropped when no database objects refer to it.
@@ -62,6 +73,15 @@ pub struct Transaction<'l, S: Sequencer, P: PersistenceLayer<S>> {
 /// The lower three bits are always zero.
 pub type ID = u64;
 
+/// The type of transaction serial numbers.
+///
+/// A serial number is assigned to each transaction in the order of creation, and it is never
+/// reused in the process; a transaction with a smaller serial number is older.
+pub(super) type Serial = u64;
+
+/// The serial number of the next transaction.
+static NEXT_SERIAL: AtomicU64 = AtomicU64::new(1);
+
 /// Possible [`Transaction`] states.
 #[derive(Clone, Copy, Eq, Debug, Ord, PartialEq, PartialOrd)]
 pub enum State {
@@ -81,11 +101,27 @@ pub enum State {
     RolledBack,
 }
 
+/// [`HookEvent`] is passed to the hooks registered in a [`Transaction`] when it is ended.
+pub enum HookEvent<S: Sequencer> {
+    /// The transaction was committed at the instant.
+    Committed(S::Instant),
+
+    /// The transaction was rolled back.
+    RolledBack,
+}
+
+/// [`Hook`] is a callback that is invoked once when the [`Transaction`] is ended.
+pub type Hook<S> = Box<dyn FnOnce(HookEvent<S>) + Send>;
+
 /// [`Committable`] gives one last chance of rolling back the transaction.
 ///
 /// The transaction is bound to be rolled back if no actions are taken before dropping the
 /// [`Committable`] instance. On the other hands, the transaction stays uncommitted until the
 /// [`Committable`] instance is dropped or awaited.
+///
+/// If the transaction was partially rolled back via [`Transaction::rewind`] or
+/// [`Transaction::rollback_to`], only the changes that survived the partial rollback are
+/// committed.
 pub struct Committable<'d, S: Sequencer, P: PersistenceLayer<S>> {
     /// The corresponding transaction.
     transaction: Option<Transaction<'d, S, P>>,
@@ -94,6 +130,21 @@ pub struct Committable<'d, S: Sequencer, P: PersistenceLayer<S>> {
     commit_log_io: Option<(AwaitIO<'d, S, P>, S::Instant)>,
 }
 
+/// [`Savepoint`] is a point of time in a [`Transaction`] that the transaction can roll back to.
+///
+/// A [`Savepoint`] captures the last submitted [`Journal`] when it was created, and
+/// [`Transaction::rollback_to`] rolls back all the [`Journal`] instances submitted after the
+/// [`Savepoint`] was created. A [`Savepoint`] becomes invalid if the [`Journal`] it captured is
+/// rolled back, or the [`Transaction`] is ended.
+#[derive(Clone, Debug)]
+pub struct Savepoint<S: Sequencer> {
+    /// The [`Transaction`] that created the [`Savepoint`].
+    transaction_anchor: ebr::Arc<Anchor<S>>,
+
+    /// The last submitted [`Journal`] when the [`Savepoint`] was created.
+    journal_anchor: Option<ebr::Arc<JournalAnchor<S>>>,
+}
+
 /// `u32::MAX - 1` is the last clock value that a transaction clock can reach.
 ///
 /// [`Transaction`] cannot generate a clock value that is greater than [`MAX_TRANSACTION_INSTANT`],
@@ -134,6 +185,13 @@ pub struct Playback<'d, S: Sequencer, P: PersistenceLayer<S>> {
     anchor: ebr::Arc<Anchor<S>>,
 }
 
+/// Hooks registered in a [`Transaction`] or a [`Journal`].
+///
+/// Each hook is tagged with the clock value of the [`Transaction`] that it depends on; the hook is
+/// dropped when the [`Transaction`] is rewound to an earlier time point. The [`Mutex`] is only
+/// locked when a [`Journal`] is submitted, otherwise it is accessed through a mutable reference.
+pub(super) struct Hooks<S: Sequencer>(Mutex<Vec<(u32, Hook<S>)>>);
+
 /// [Anchor] contains data that is required to outlive the [Transaction] instance.
 #[derive(Debug)]
 #[repr(align(16))]
@@ -156,6 +214,9 @@ pub(super) struct Anchor<S: Sequencer> {
 
     /// An unordered bag of [`Waker`] for readers.
     waiting_readers: Bag<Waker, 4>,
+
+    /// The serial number of the transaction.
+    serial: Serial,
 }
 
 impl<'d, S: Sequencer, P: PersistenceLayer<S>> Transaction<'d, S, P> {
@@ -240,7 +301,7 @@ impl<'d, S: Sequencer, P: PersistenceLayer<S>> Transaction<'d, S, P> {
             if own_xid.as_ref() == xid {
                 Ok(())
             } else {
-                Err(Error::UnexpectedState)
+                Err(Error::UnexpectedState(None))
             }
         } else {
             let io_completion = self
@@ -331,6 +392,11 @@ impl<'d, S: Sequencer, P: PersistenceLayer<S>> Transaction<'d, S, P> {
         }
         let new_instant = current.as_ref().and_then(|r| r.submit_instant());
         self.journal_strand.swap((current, ebr::Tag::None), Relaxed);
+        if self.anchor.state.load(Relaxed) == State::Active.into() {
+            // Hooks registered after the instant are dropped without being invoked.
+            let rewind_to = new_instant.map_or(0, NonZeroU32::get);
+            self.hooks.get_mut().retain(|(i, _)| *i <= rewind_to);
+        }
 
         if let Some(eot_log_buffer) = self.eot_log_buffer.take() {
             self.database
@@ -344,6 +410,164 @@ impl<'d, S: Sequencer, P: PersistenceLayer<S>> Transaction<'d, S, P> {
         Ok(new_instant)
     }
 
+    /// Creates a [`Savepoint`] that the [`Transaction`] can roll back to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::num::NonZeroU32;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("savepoint")).await.unwrap();
+    ///     let transaction = database.transaction();
+    ///     let journal = transaction.journal();
+    ///     journal.submit();
+    ///     let savepoint = transaction.savepoint();
+    /// };
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn savepoint(&self) -> Savepoint<S> {
+        Savepoint {
+            transaction_anchor: self.anchor.clone(),
+            journal_anchor: self
+                .journal_strand
+                .load(Acquire, &ebr::Barrier::new())
+                .get_arc(),
+        }
+    }
+
+    /// Rolls back all the changes made after the [`Savepoint`] was created.
+    ///
+    /// It returns the updated clock value, and the [`Transaction`] stays active after the partial
+    /// rollback. The [`Savepoint`] stays valid after the partial rollback, therefore the
+    /// [`Transaction`] can roll back to the same [`Savepoint`] more than once.
+    ///
+    /// # Errors
+    ///
+    /// An [`Error`] is returned if the corresponding log record could not be constructed.
+    /// [`Error::WrongParameter`] is returned if the [`Savepoint`] was created by another
+    /// [`Transaction`], or the [`Transaction`] was rolled back to a point of time before the
+    /// [`Savepoint`] was created.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::num::NonZeroU32;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("rollback_to")).await.unwrap();
+    ///     let mut transaction = database.transaction();
+    ///     let journal = transaction.journal();
+    ///     journal.submit();
+    ///     let savepoint = transaction.savepoint();
+    ///     let journal = transaction.journal();
+    ///     journal.submit();
+    ///     assert_eq!(transaction.rollback_to(&savepoint), Ok(NonZeroU32::new(1)));
+    ///     assert!(transaction.commit().await.is_ok());
+    /// };
+    /// ```
+    #[inline]
+    pub fn rollback_to(&mut self, savepoint: &Savepoint<S>) -> Result<Option<NonZeroU32>, Error> {
+        if savepoint.transaction_anchor.as_ptr() != self.anchor.as_ptr() {
+            // The savepoint belongs to another transaction.
+            return Err(Error::WrongParameter);
+        }
+        let instant = if let Some(journal_anchor) = savepoint.journal_anchor.as_ref() {
+            if journal_anchor.is_rolled_back() {
+                // The savepoint was passed by a previous partial rollback.
+                return Err(Error::WrongParameter);
+            }
+            journal_anchor.submit_instant()
+        } else {
+            None
+        };
+        let new_instant = self.rewind(instant)?;
+        if self.eot_log_buffer.is_none() {
+            // `rewind(None)` assumes that the transaction is being rolled back.
+            self.eot_log_buffer.replace(Arc::default());
+        }
+        Ok(new_instant)
+    }
+
+    /// Returns the [`CancellationToken`] of the [`Transaction`].
+    ///
+    /// Cancelling the returned token makes all the waiting access requests of the [`Transaction`]
+    /// return [`Error::Cancelled`], whereas the changes made by the [`Transaction`] are kept; the
+    /// [`Transaction`] can still be committed or rolled back afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::{Database, Error};
+    /// use std::path::Path;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("cancellation_token")).await.unwrap();
+    ///     let access_controller = database.access_controller();
+    ///     let owner = database.transaction();
+    ///     let mut journal = owner.journal();
+    ///     assert!(access_controller.lock(1, &mut journal, None).await.is_ok());
+    ///     journal.submit();
+    ///
+    ///     let waiter = database.transaction();
+    ///     waiter.cancellation_token().cancel();
+    ///     let mut journal = waiter.journal();
+    ///     let deadline = Some(Instant::now() + Duration::from_secs(60));
+    ///     assert_eq!(
+    ///         access_controller.lock(1, &mut journal, deadline).await,
+    ///         Err(Error::Cancelled)
+    ///     );
+    /// };
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation_token.clone()
+    }
+
+    /// Registers a hook that is invoked when the [`Transaction`] is ended.
+    ///
+    /// Hooks are invoked in registration order after the final state of the [`Transaction`] is
+    /// visible to all the other transactions; hooks registered in a [`Journal`] are registered
+    /// when the [`Journal`] is submitted. A hook is dropped without being invoked if the
+    /// [`Transaction`] is rewound to a time point before the hook was registered, whereas rolling
+    /// back to a [`Savepoint`] created after the hook was registered keeps the hook. A panicking
+    /// hook does not prevent the other hooks from being invoked, and the first panic is resumed
+    /// after all the hooks were invoked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::{Database, HookEvent};
+    /// use std::path::Path;
+    /// use std::sync::atomic::AtomicBool;
+    /// use std::sync::atomic::Ordering::Relaxed;
+    /// use std::sync::Arc;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("register_hook")).await.unwrap();
+    ///     let mut transaction = database.transaction();
+    ///     let committed = Arc::new(AtomicBool::new(false));
+    ///     let committed_clone = committed.clone();
+    ///     transaction.register_hook(Box::new(move |e| {
+    ///         committed_clone.store(matches!(e, HookEvent::Committed(_)), Relaxed);
+    ///     }));
+    ///     assert!(transaction.commit().await.is_ok());
+    ///     assert!(committed.load(Relaxed));
+    /// };
+    /// ```
+    #[inline]
+    pub fn register_hook(&mut self, hook: Hook<S>) {
+        let instant = self.now().map_or(0, NonZeroU32::get);
+        self.hooks.push(instant, hook);
+    }
+
     /// Prepares the [`Transaction`] for commit.
     ///
     /// It returns a [`Committable`], giving one last chance to roll back the prepared
@@ -417,9 +641,23 @@ impl<'d, S: Sequencer, P: PersistenceLayer<S>> Transaction<'d, S, P> {
     /// };
     /// ```
     #[inline]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            ret,
+            fields(transaction_id = self.id(), now = ?self.now())
+        )
+    )]
     pub async fn commit(self) -> Result<S::Instant, Error> {
+        let stopwatch = Stopwatch::start();
         let indoubt_transaction = self.prepare().await?;
-        indoubt_transaction.await
+        let result = indoubt_transaction.await;
+        if result.is_ok() {
+            stopwatch.record_commit();
+        }
+        result
     }
 
     /// Rolls back the changes made by the [`Transaction`].
@@ -442,6 +680,14 @@ impl<'d, S: Sequencer, P: PersistenceLayer<S>> Transaction<'d, S, P> {
     /// };
     /// ```
     #[inline]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(transaction_id = self.id(), now = ?self.now())
+        )
+    )]
     pub fn rollback(mut self) {
         self.rollback_internal();
         drop(self);
@@ -455,6 +701,8 @@ impl<'d, S: Sequencer, P: PersistenceLayer<S>> Transaction<'d, S, P> {
             eot_log_buffer: Some(Arc::default()),
             journal_strand: ebr::AtomicArc::null(),
             xid: None,
+            hooks: Hooks::default(),
+            cancellation_token: CancellationToken::default(),
             anchor: ebr::Arc::new(Anchor::new()),
         }
     }
@@ -469,6 +717,11 @@ impl<'d, S: Sequencer, P: PersistenceLayer<S>> Transaction<'d, S, P> {
         self.database
     }
 
+    /// Takes the hooks registered in a [`Journal`] submitted at the specified instant.
+    pub(super) fn submit_hooks(&self, submit_instant: NonZeroU32, hooks: &mut Hooks<S>) {
+        self.hooks.append(submit_instant.get(), hooks);
+    }
+
     /// Submits a [`Journal`].
     pub(super) fn submit_journal(
         &self,
@@ -564,7 +817,7 @@ impl<'d, S: Sequencer, P: PersistenceLayer<S>> Transaction<'d, S, P> {
             );
             Ok((io_completion, commit_instant))
         } else {
-            Err(Error::UnexpectedState)
+            Err(Error::UnexpectedState(None))
         }
     }
 
@@ -586,6 +839,8 @@ impl<'d, S: Sequencer, P: PersistenceLayer<S>> Transaction<'d, S, P> {
             record.commit(self.database.task_processor());
             current = record.set_next(None, Relaxed).0;
         }
+
+        self.hooks.run(HookEvent::Committed(commit_instant));
     }
 
     /// Rolls back all the changes.
@@ -601,6 +856,8 @@ impl<'d, S: Sequencer, P: PersistenceLayer<S>> Transaction<'d, S, P> {
         debug_assert_eq!(result, Ok(None));
 
         self.anchor.state.store(State::RolledBack.into(), Release);
+
+        self.hooks.run(HookEvent::RolledBack);
     }
 }
 
@@ -614,6 +871,64 @@ impl<'d, S: Sequencer, P: PersistenceLayer<S>> Drop for Transaction<'d, S, P> {
     }
 }
 
+impl<'d, S: Sequencer, P: PersistenceLayer<S>> Committable<'d, S, P> {
+    /// Commits the prepared [`Transaction`].
+    ///
+    /// It is equivalent to awaiting the [`Committable`].
+    ///
+    /// # Errors
+    ///
+    /// If the transaction cannot be committed, an [`Error`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("committable_commit")).await.unwrap();
+    ///     let transaction = database.transaction();
+    ///     let committable = transaction.prepare().await.unwrap();
+    ///     assert!(committable.commit().await.is_ok());
+    /// };
+    /// ```
+    #[inline]
+    pub async fn commit(self) -> Result<S::Instant, Error> {
+        self.await
+    }
+
+    /// Rolls back the prepared [`Transaction`].
+    ///
+    /// Readers that have been waiting for the final state of the [`Transaction`] are woken up, and
+    /// see none of the changes made by the [`Transaction`].
+    ///
+    /// # Panics
+    ///
+    /// Any failure when rolling back the transaction, e.g., memory allocation failure or an IO
+    /// error, will lead to a panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sap_tsf::Database;
+    /// use std::path::Path;
+    ///
+    /// async {
+    ///     let database = Database::with_path(Path::new("committable_rollback")).await.unwrap();
+    ///     let transaction = database.transaction();
+    ///     let committable = transaction.prepare().await.unwrap();
+    ///     committable.rollback();
+    /// };
+    /// ```
+    #[inline]
+    pub fn rollback(mut self) {
+        if let Some(transaction) = self.transaction.take() {
+            transaction.rollback();
+        }
+    }
+}
+
 impl<'d, S: Sequencer, P: PersistenceLayer<S>> Future for Committable<'d, S, P> {
     type Output = Result<S::Instant, Error>;
 
@@ -659,7 +974,84 @@ impl<'d, S: Sequencer, P: PersistenceLayer<S>> Future for Committable<'d, S, P>
         }
 
         // Already awaited.
-        Poll::Ready(Err(Error::UnexpectedState))
+        Poll::Ready(Err(Error::UnexpectedState(None)))
+    }
+}
+
+impl<S: Sequencer> Clone for HookEvent<S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S: Sequencer> Copy for HookEvent<S> {}
+
+impl<S: Sequencer> fmt::Debug for HookEvent<S> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Committed(commit_instant) => {
+                f.debug_tuple("Committed").field(commit_instant).finish()
+            }
+            Self::RolledBack => f.write_str("RolledBack"),
+        }
+    }
+}
+
+impl<S: Sequencer> Hooks<S> {
+    /// Returns a mutable reference to the registered hooks.
+    fn get_mut(&mut self) -> &mut Vec<(u32, Hook<S>)> {
+        self.0.get_mut().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Registers a hook tagged with the specified instant.
+    pub(super) fn push(&mut self, instant: u32, hook: Hook<S>) {
+        self.get_mut().push((instant, hook));
+    }
+
+    /// Moves all the hooks in `other` to `self`, and tags them with the specified instant.
+    fn append(&self, instant: u32, other: &mut Hooks<S>) {
+        let hooks = take(other.get_mut());
+        if !hooks.is_empty() {
+            self.0
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .extend(hooks.into_iter().map(|(_, hook)| (instant, hook)));
+        }
+    }
+
+    /// Invokes all the registered hooks in registration order.
+    ///
+    /// A panic in a hook is caught, and the first one is resumed after all the hooks were invoked
+    /// unless the thread is already panicking.
+    fn run(&mut self, event: HookEvent<S>) {
+        let mut first_panic = None;
+        for (_, hook) in take(self.get_mut()) {
+            if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| hook(event))) {
+                first_panic.get_or_insert(payload);
+            }
+        }
+        if let Some(payload) = first_panic {
+            if !std::thread::panicking() {
+                panic::resume_unwind(payload);
+            }
+        }
+    }
+}
+
+impl<S: Sequencer> fmt::Debug for Hooks<S> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let len = self.0.lock().map_or(0, |h| h.len());
+        f.debug_struct("Hooks").field("len", &len).finish()
+    }
+}
+
+impl<S: Sequencer> Default for Hooks<S> {
+    #[inline]
+    fn default() -> Self {
+        Self(Mutex::new(Vec::new()))
     }
 }
 
@@ -837,9 +1229,15 @@ impl<S: Sequencer> Anchor<S> {
             prepare_instant: S::Instant::default(),
             commit_instant: S::Instant::default(),
             waiting_readers: Bag::new(),
+            serial: NEXT_SERIAL.fetch_add(1, Relaxed),
         }
     }
 
+    /// Returns the serial number of the transaction.
+    pub(super) fn serial(&self) -> Serial {
+        self.serial
+    }
+
     /// Returns the instant when the transaction was being prepared for commit.
     pub(super) fn prepare_instant(&self) -> Option<S::Instant> {
         let state = self.state.load(Acquire);
@@ -886,6 +1284,7 @@ impl<S: Sequencer> Anchor<S> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use futures::FutureExt;
     use static_assertions::assert_eq_size;
     use std::{path::Path, sync::Arc};
     use tokio::{fs::remove_dir_all, sync::Barrier};
@@ -911,6 +1310,28 @@ mod tests {
         assert!(remove_dir_all(path).await.is_ok());
     }
 
+    #[tokio::test]
+    async fn now() {
+        const DIR: &str = "transaction_now_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let transaction = database.transaction();
+        assert!(transaction.now().is_none());
+        let journal = transaction.journal();
+        assert!(transaction.now().is_none());
+        let instant = journal.submit();
+        assert_eq!(transaction.now(), Some(instant));
+        assert_eq!(transaction.now(), Some(instant));
+        let journal = transaction.journal();
+        let next_instant = journal.submit();
+        assert!(next_instant > instant);
+        assert_eq!(transaction.now(), Some(next_instant));
+        assert_eq!(transaction.now(), Some(next_instant));
+        assert!(transaction.commit().await.is_ok());
+        drop(database);
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 16)]
     async fn rewind() {
         const DIR: &str = "transaction_rewind_test";
@@ -956,4 +1377,340 @@ mod tests {
         drop(database);
         assert!(remove_dir_all(path).await.is_ok());
     }
+
+    #[tokio::test]
+    async fn savepoint() {
+        const DIR: &str = "transaction_savepoint_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        let mut transaction = database.transaction();
+        let initial_savepoint = transaction.savepoint();
+        let mut journal = transaction.journal();
+        assert_eq!(
+            access_controller.lock(1, &mut journal, None).await,
+            Ok(true)
+        );
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+
+        let savepoint = transaction.savepoint();
+        for id in 2..4 {
+            let mut journal = transaction.journal();
+            assert_eq!(
+                access_controller.lock(id, &mut journal, None).await,
+                Ok(true)
+            );
+            assert_eq!(u64::from(journal.submit().get()), id);
+        }
+        assert_eq!(transaction.now(), NonZeroU32::new(3));
+        assert_eq!(transaction.rollback_to(&savepoint), Ok(NonZeroU32::new(1)));
+        assert_eq!(transaction.now(), NonZeroU32::new(1));
+
+        // Only the changes made before the savepoint are kept.
+        let other_transaction = database.transaction();
+        let mut other_journal = other_transaction.journal();
+        assert_eq!(
+            access_controller.lock(1, &mut other_journal, None).await,
+            Err(Error::Conflict)
+        );
+        assert_eq!(
+            access_controller.lock(2, &mut other_journal, None).await,
+            Ok(true)
+        );
+        drop(other_journal);
+        drop(other_transaction);
+
+        assert_eq!(transaction.rollback_to(&initial_savepoint), Ok(None));
+        let journal = transaction.journal();
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+        assert!(transaction.commit().await.is_ok());
+        drop(database);
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn prepare() {
+        const DIR: &str = "transaction_prepare_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        for commit in [false, true] {
+            let transaction = database.transaction();
+            let mut journal = transaction.journal();
+            assert!(access_controller
+                .create(0, &mut journal, None)
+                .await
+                .is_ok());
+            assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+            let committable = transaction.prepare().await.unwrap();
+
+            // Readers wait for the final state of the prepared transaction.
+            let snapshot = database.snapshot();
+            let (read_result, commit_result) = futures::join!(
+                access_controller.read(
+                    0,
+                    &snapshot,
+                    Some(std::time::Instant::now() + std::time::Duration::from_secs(60))
+                ),
+                async {
+                    if commit {
+                        committable.commit().await.is_ok()
+                    } else {
+                        committable.rollback();
+                        true
+                    }
+                }
+            );
+            assert!(commit_result);
+            assert!(read_result.is_ok());
+            drop(snapshot);
+
+            let snapshot = database.snapshot();
+            assert_eq!(access_controller.read(0, &snapshot, None).await, Ok(commit));
+        }
+        drop(database);
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn commit_cancel() {
+        const DIR: &str = "transaction_commit_cancel_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        for object_id in 0..3 {
+            let transaction = database.transaction();
+            let mut journal = transaction.journal();
+            assert_eq!(
+                access_controller
+                    .create(object_id, &mut journal, None)
+                    .await,
+                Ok(true)
+            );
+            assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+            let committed = match object_id {
+                0 => {
+                    // The commit future is dropped before being polled.
+                    drop(transaction.commit());
+                    false
+                }
+                1 => {
+                    // The prepared transaction is dropped without being committed.
+                    drop(transaction.prepare().await.unwrap());
+                    false
+                }
+                _ => {
+                    // The commit future is dropped after being polled once.
+                    let mut commit = Box::pin(transaction.commit());
+                    let committed = matches!(futures::poll!(commit.as_mut()), Poll::Ready(Ok(_)));
+                    drop(commit);
+                    committed
+                }
+            };
+
+            let snapshot = database.snapshot();
+            assert_eq!(
+                access_controller.read(object_id, &snapshot, None).await,
+                Ok(committed)
+            );
+            drop(snapshot);
+
+            let transaction = database.transaction();
+            let mut journal = transaction.journal();
+            if committed {
+                assert!(access_controller
+                    .create(object_id, &mut journal, None)
+                    .await
+                    .is_err());
+            } else {
+                assert_eq!(
+                    access_controller
+                        .create(object_id, &mut journal, None)
+                        .await,
+                    Ok(true)
+                );
+            }
+        }
+        drop(database);
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn rollback_wake_up() {
+        const DIR: &str = "transaction_rollback_wake_up_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        let transaction = database.transaction();
+        let mut journal = transaction.journal();
+        assert!(access_controller.lock(0, &mut journal, None).await.is_ok());
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+
+        let waiting_transaction = database.transaction();
+        let mut waiting_journal = waiting_transaction.journal();
+        let (result, ()) = futures::join!(
+            access_controller.lock(
+                0,
+                &mut waiting_journal,
+                Some(std::time::Instant::now() + std::time::Duration::from_secs(60))
+            ),
+            async move { transaction.rollback() }
+        );
+        assert_eq!(result, Ok(true));
+        assert_eq!(Some(waiting_journal.submit()), NonZeroU32::new(1));
+        assert!(waiting_transaction.commit().await.is_ok());
+        drop(database);
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn savepoint_validity() {
+        const DIR: &str = "transaction_savepoint_validity_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let access_controller = database.access_controller();
+        let mut transaction = database.transaction();
+        let mut savepoints = Vec::new();
+        for id in 0..3 {
+            let mut journal = transaction.journal();
+            assert_eq!(
+                access_controller.create(id, &mut journal, None).await,
+                Ok(true)
+            );
+            assert_eq!(u64::from(journal.submit().get()), id + 1);
+            savepoints.push(transaction.savepoint());
+        }
+
+        // The changes made by the later two journals become invisible.
+        assert_eq!(
+            transaction.rollback_to(&savepoints[0]),
+            Ok(NonZeroU32::new(1))
+        );
+        assert_eq!(
+            transaction.rollback_to(&savepoints[0]),
+            Ok(NonZeroU32::new(1))
+        );
+        let snapshot = transaction.snapshot();
+        assert_eq!(access_controller.read(0, &snapshot, None).await, Ok(true));
+        assert_eq!(access_controller.read(1, &snapshot, None).await, Ok(false));
+        assert_eq!(access_controller.read(2, &snapshot, None).await, Ok(false));
+        drop(snapshot);
+
+        // Savepoints created after the first journal have been passed.
+        let journal = transaction.journal();
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(2));
+        assert_eq!(
+            transaction.rollback_to(&savepoints[1]),
+            Err(Error::WrongParameter)
+        );
+        assert_eq!(
+            transaction.rollback_to(&savepoints[2]),
+            Err(Error::WrongParameter)
+        );
+        assert_eq!(transaction.now(), NonZeroU32::new(2));
+
+        // Savepoints cannot be shared among transactions.
+        let mut other_transaction = database.transaction();
+        assert_eq!(
+            other_transaction.rollback_to(&savepoints[0]),
+            Err(Error::WrongParameter)
+        );
+        drop(other_transaction);
+        assert!(transaction.commit().await.is_ok());
+        drop(savepoints);
+        drop(database);
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn hooks() {
+        const DIR: &str = "transaction_hooks_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let register = |transaction: &mut Transaction<_, _>, tag: usize| {
+            let events = events.clone();
+            transaction.register_hook(Box::new(move |e| {
+                let committed = matches!(e, HookEvent::Committed(_));
+                events.lock().unwrap().push((tag, committed));
+            }));
+        };
+
+        let mut transaction = database.transaction();
+        register(&mut transaction, 0);
+        let journal = transaction.journal();
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+        let savepoint = transaction.savepoint();
+        let mut journal = transaction.journal();
+        let events_clone = events.clone();
+        journal.register_hook(Box::new(move |e| {
+            let committed = matches!(e, HookEvent::Committed(_));
+            events_clone.lock().unwrap().push((1, committed));
+        }));
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(2));
+        register(&mut transaction, 2);
+        assert_eq!(transaction.rollback_to(&savepoint), Ok(NonZeroU32::new(1)));
+        transaction.register_hook(Box::new(|_| panic!("hook")));
+        register(&mut transaction, 3);
+        assert!(events.lock().unwrap().is_empty());
+
+        let committable = transaction.prepare().await.unwrap();
+        assert!(AssertUnwindSafe(committable).catch_unwind().await.is_err());
+        assert_eq!(*events.lock().unwrap(), vec![(0, true), (3, true)]);
+        events.lock().unwrap().clear();
+
+        let mut transaction = database.transaction();
+        register(&mut transaction, 4);
+        register(&mut transaction, 5);
+        transaction.rollback();
+        assert_eq!(*events.lock().unwrap(), vec![(4, false), (5, false)]);
+
+        drop(database);
+        assert!(remove_dir_all(path).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn hooks_savepoint() {
+        const DIR: &str = "transaction_hooks_savepoint_test";
+        let path = Path::new(DIR);
+        let database = Database::with_path(path).await.unwrap();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let register = |transaction: &mut Transaction<_, _>, tag: usize| {
+            let events = events.clone();
+            transaction.register_hook(Box::new(move |e| {
+                let committed = matches!(e, HookEvent::Committed(_));
+                events.lock().unwrap().push((tag, committed));
+            }));
+        };
+
+        // Hooks registered before the savepoint was created are kept.
+        let mut transaction = database.transaction();
+        register(&mut transaction, 0);
+        let savepoint = transaction.savepoint();
+        assert_eq!(transaction.rollback_to(&savepoint), Ok(None));
+        let journal = transaction.journal();
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(1));
+        register(&mut transaction, 1);
+        let savepoint = transaction.savepoint();
+        let journal = transaction.journal();
+        assert_eq!(Some(journal.submit()), NonZeroU32::new(2));
+        assert_eq!(transaction.rollback_to(&savepoint), Ok(NonZeroU32::new(1)));
+        assert!(transaction.commit().await.is_ok());
+        assert_eq!(*events.lock().unwrap(), vec![(0, true), (1, true)]);
+
+        // Hooks registered in a journal that was not submitted are dropped.
+        events.lock().unwrap().clear();
+        let transaction = database.transaction();
+        let mut journal = transaction.journal();
+        let events_clone = events.clone();
+        journal.register_hook(Box::new(move |_| {
+            events_clone.lock().unwrap().push((2, true))
+        }));
+        drop(journal);
+        assert!(transaction.commit().await.is_ok());
+        assert!(events.lock().unwrap().is_empty());
+
+        drop(database);
+        assert!(remove_dir_all(path).await.is_ok());
+    }
 }